@@ -0,0 +1,30 @@
+//! Exposes the resolved `rug` version as `PI_RUG_VERSION` for run reports.
+//!
+//! Cargo does not pass dependency versions to a crate, so read it from
+//! Cargo.lock when there is one next to the manifest and fall back to
+//! "unknown" otherwise (e.g. when built as a dependency).
+
+use std::fs;
+
+fn main() {
+    println!("cargo:rerun-if-changed=Cargo.lock");
+
+    let version = fs::read_to_string("Cargo.lock")
+        .ok()
+        .and_then(|lock| {
+            let mut lines = lock.lines();
+            while let Some(line) = lines.next() {
+                if line == "name = \"rug\"" {
+                    let next = lines.next()?;
+                    return next
+                        .strip_prefix("version = \"")
+                        .and_then(|v| v.strip_suffix('"'))
+                        .map(str::to_string);
+                }
+            }
+            None
+        })
+        .unwrap_or_else(|| "unknown".to_string());
+
+    println!("cargo:rustc-env=PI_RUG_VERSION={}", version);
+}
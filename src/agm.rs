@@ -0,0 +1,116 @@
+//! Gauss–Legendre (Brent–Salamin) iteration for π.
+//!
+//!   a₀ = 1, b₀ = 1/√2, t₀ = 1/4, p₀ = 1
+//!   aₙ₊₁ = (aₙ + bₙ)/2, bₙ₊₁ = √(aₙbₙ), tₙ₊₁ = tₙ - pₙ(aₙ - aₙ₊₁)², pₙ₊₁ = 2pₙ
+//!   π ≈ (aₙ + bₙ)² / 4tₙ
+//!
+//! The number of correct digits doubles with every step, so a run is
+//! O(log n) full-precision multiplications and square roots. It shares
+//! nothing with the Chudnovsky series, which makes it a good cross-check.
+//!
+//! The plain AGM also gives the lemniscate constant and Γ(1/4) from π:
+//!
+//!   ϖ = π / AGM(1, √2),   Γ(1/4) = √(2ϖ·√(2π))
+
+use rug::Float;
+
+/// Extra bits carried through the iteration to absorb rounding.
+const GUARD_BITS: u64 = 64;
+
+/// π to `precision` bits, and the number of iterations it took.
+pub fn pi_agm(precision: u64) -> (Float, u64) {
+    let prec = precision + GUARD_BITS;
+
+    let mut a = Float::with_val_64(prec, 1);
+    let mut b = Float::with_val_64(prec, 0.5).sqrt();
+    let mut t = Float::with_val_64(prec, 0.25);
+    let mut p = Float::with_val_64(prec, 1);
+    let mut steps = 0;
+
+    // Stop once a and b agree to the working precision; the error of the
+    // next step is then far below it
+    loop {
+        let diff = Float::with_val_64(prec, &a - &b);
+        let exp = diff.get_exp().map_or(i64::MIN, i64::from);
+        if exp < -(prec as i64 / 2) {
+            break;
+        }
+
+        let next_a = Float::with_val_64(prec, &a + &b) / 2u32;
+        b = Float::with_val_64(prec, &a * &b).sqrt();
+
+        let delta = Float::with_val_64(prec, &a - &next_a).square();
+        t -= delta * &p;
+        p *= 2u32;
+        a = next_a;
+        steps += 1;
+    }
+
+    let sum = Float::with_val_64(prec, &a + &b).square();
+    let pi = sum / (t * 4u32);
+    (Float::with_val_64(precision, &pi), steps)
+}
+
+/// AGM(a, b) at the precision of `a`, and the number of iterations.
+pub fn agm(mut a: Float, mut b: Float) -> (Float, u64) {
+    let prec = a.prec_64();
+    let mut steps = 0;
+
+    // Once a and b agree to half the precision, one more mean is exact to
+    // the full precision
+    loop {
+        let diff = Float::with_val_64(prec, &a - &b);
+        let exp = diff.get_exp().map_or(i64::MIN, i64::from);
+        steps += 1;
+        if exp < -(prec as i64 / 2) {
+            return ((a + b) / 2u32, steps);
+        }
+
+        let next_a = Float::with_val_64(prec, &a + &b) / 2u32;
+        b = Float::with_val_64(prec, &a * &b).sqrt();
+        a = next_a;
+    }
+}
+
+/// Lemniscate constant ϖ = π / AGM(1, √2) = 2.6220575... to `precision`
+/// bits, given π to at least that precision, and the AGM iterations.
+pub fn lemniscate(pi: &Float, precision: u64) -> (Float, u64) {
+    let prec = precision + GUARD_BITS;
+    let one = Float::with_val_64(prec, 1);
+    let sqrt2 = Float::with_val_64(prec, 2).sqrt();
+    let (m, steps) = agm(one, sqrt2);
+    let lemniscate = Float::with_val_64(prec, pi) / m;
+    (Float::with_val_64(precision, &lemniscate), steps)
+}
+
+/// Γ(1/4) = √(2ϖ·√(2π)) = 3.6256099... to `precision` bits, given π to
+/// at least that precision, and the AGM iterations.
+pub fn gamma_quarter(pi: &Float, precision: u64) -> (Float, u64) {
+    let prec = precision + GUARD_BITS;
+    let (lemniscate, steps) = lemniscate(pi, prec);
+    let sqrt_2pi = (Float::with_val_64(prec, pi) * 2u32).sqrt();
+    let gamma = (lemniscate * 2u32 * sqrt_2pi).sqrt();
+    (Float::with_val_64(precision, &gamma), steps)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ComputeOptions, Constant, compute_constant};
+
+    #[test]
+    fn lemniscate_and_gamma_quarter_digits() {
+        let digits = |constant| {
+            compute_constant(constant, 50, &ComputeOptions::default())
+                .unwrap()
+                .to_digit_string()
+        };
+        assert_eq!(
+            digits(Constant::Lemniscate),
+            "2.62205755429211981046483958989111941368275495143162"
+        );
+        assert_eq!(
+            digits(Constant::GammaQuarter),
+            "3.62560990822190831193068515586767200299516768288006"
+        );
+    }
+}
@@ -0,0 +1,77 @@
+//! Square roots, cube roots and the golden ratio by Newton iteration.
+//!
+//!   x ← ((r-1)·x + N / x^(r-1)) / r
+//!
+//! converges quadratically to N^(1/r), so starting from the f64 root each
+//! step runs at about twice the precision of the previous one and only the
+//! last step costs a full-precision division.
+//!
+//! Roots of perfect powers are returned exactly: a Newton iterate just
+//! below an integer root would otherwise truncate to ...999.
+
+use rug::ops::Pow;
+use rug::{Float, Integer};
+
+/// Bits an f64 starting value is trusted for.
+const START_BITS: u64 = 40;
+
+/// N^(1/r) to `precision` bits and the number of Newton steps taken.
+pub fn root(n: u64, r: u32, precision: u64) -> (Float, u64) {
+    let exact = Integer::from(n).root(r);
+    if Integer::from((&exact).pow(r)) == n {
+        return (Float::with_val_64(precision, exact), 0);
+    }
+
+    // Precisions of the steps, halving down from the target
+    let mut steps = vec![precision];
+    let mut prec = precision;
+    while prec > START_BITS {
+        prec = prec / 2 + 8;
+        steps.push(prec);
+    }
+
+    let mut x = Float::with_val_64(START_BITS, (n as f64).powf(1.0 / f64::from(r)));
+    for &prec in steps.iter().rev() {
+        x.set_prec_64(prec);
+        let power = Float::with_val_64(prec, (&x).pow(r - 1));
+        let quotient = Float::with_val_64(prec, n) / power;
+        x *= r - 1;
+        x += quotient;
+        x /= r;
+    }
+    (x, steps.len() as u64)
+}
+
+/// φ = (1 + √5) / 2 to `precision` bits and the number of Newton steps.
+pub fn phi(precision: u64) -> (Float, u64) {
+    let (sqrt5, steps) = root(5, 2, precision);
+    ((sqrt5 + 1u32) / 2u32, steps)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ComputeOptions, Constant, compute_constant};
+
+    #[test]
+    fn root_digits() {
+        let digits = |constant| {
+            compute_constant(constant, 50, &ComputeOptions::default())
+                .unwrap()
+                .to_digit_string()
+        };
+        assert_eq!(
+            digits(Constant::Sqrt(2)),
+            "1.41421356237309504880168872420969807856967187537694"
+        );
+        assert_eq!(
+            digits(Constant::Cbrt(2)),
+            "1.25992104989487316476721060727822835057025146470150"
+        );
+        assert_eq!(
+            digits(Constant::Phi),
+            "1.61803398874989484820458683436563811772030917980576"
+        );
+        // Perfect powers are exact, not ...999
+        assert_eq!(digits(Constant::Cbrt(27)), format!("3.{}", "0".repeat(50)));
+    }
+}
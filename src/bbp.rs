@@ -0,0 +1,177 @@
+//! Bailey–Borwein–Plouffe hexadecimal digit extraction.
+//!
+//!   π = Σ_k 16^-k (4/(8k+1) - 2/(8k+4) - 1/(8k+5) - 1/(8k+6))
+//!
+//! Multiplying by 16^d and keeping only the fractional part turns the head
+//! of each sum into modular exponentiations, so hex digits starting at any
+//! position come out of O(d log d) word operations in constant memory,
+//! without computing the digits before them.
+//!
+//! Fractions are held as u64 fixed point (units of 2^-64) and every
+//! truncation is tracked, so only digits that are certain are returned.
+
+/// Highest supported position; keeps 8k + 6 and the error bound in range.
+pub const MAX_POSITION: u64 = 1 << 56;
+
+/// Most digits returned by one call. Every 8 to 14 digits cost a full
+/// O(d log d) round, so longer runs belong to the full computation.
+pub const MAX_COUNT: usize = 1 << 20;
+
+/// `count` hex digits of π starting at `position`, where position 1 is the
+/// first digit after the point (π = 3.243F6A88..., so position 1 is '2').
+///
+/// Each round yields the digits that the error bound pins down (about 8 to
+/// 14), then continues from the next position.
+pub fn hex_digits_at(position: u64, count: usize) -> Result<String, String> {
+    if position == 0 {
+        return Err("Hex digit positions start at 1".to_string());
+    }
+    if position > MAX_POSITION {
+        return Err(format!(
+            "Position {} is too large, max supported is {}",
+            position, MAX_POSITION
+        ));
+    }
+    if count > MAX_COUNT {
+        return Err(format!(
+            "Count {} is too large, max supported is {}",
+            count, MAX_COUNT
+        ));
+    }
+    if position + count as u64 > MAX_POSITION + 1 {
+        return Err(format!(
+            "Digits up to position {} are out of range, max supported is {}",
+            position + count as u64 - 1,
+            MAX_POSITION
+        ));
+    }
+
+    let mut digits = String::new();
+    let mut pos = position;
+    while digits.len() < count {
+        let (frac, err) = pi_frac_after(pos - 1);
+        let lo = frac.wrapping_sub(err);
+        let hi = frac.wrapping_add(err);
+
+        // Every value in [lo, hi] shares the hex digits lo and hi agree on,
+        // unless the interval wraps around 1.
+        let certain = if hi < lo {
+            0
+        } else {
+            ((lo ^ hi).leading_zeros() / 4) as usize
+        };
+        if certain == 0 {
+            return Err(format!(
+                "Hex digit at position {} is too close to a digit boundary to resolve",
+                pos
+            ));
+        }
+
+        let take = certain.min(count - digits.len());
+        for i in 0..take {
+            let nibble = (frac >> (60 - 4 * i)) & 0xF;
+            digits.push(
+                char::from_digit(nibble as u32, 16)
+                    .unwrap()
+                    .to_ascii_uppercase(),
+            );
+        }
+        pos += take as u64;
+    }
+    Ok(digits)
+}
+
+/// frac(16^d π) in units of 2^-64, with a bound on the absolute error.
+fn pi_frac_after(d: u64) -> (u64, u64) {
+    let (s1, e1) = series(d, 1);
+    let (s4, e4) = series(d, 4);
+    let (s5, e5) = series(d, 5);
+    let (s6, e6) = series(d, 6);
+
+    let frac = s1
+        .wrapping_mul(4)
+        .wrapping_sub(s4.wrapping_mul(2))
+        .wrapping_sub(s5)
+        .wrapping_sub(s6);
+    // Each sum is low by at most its error; the weights add them up.
+    (frac, 4 * e1 + 2 * e4 + e5 + e6)
+}
+
+/// frac(Σ_k 16^(d-k) / (8k + j)) in units of 2^-64, and its error bound.
+///
+/// Every term is truncated, so the result is never above the true value
+/// and is below it by less than the returned bound.
+fn series(d: u64, j: u64) -> (u64, u64) {
+    let mut sum = 0u64;
+    let mut err = 0u64;
+
+    // Head: 16^(d-k) mod (8k + j) keeps only the fractional contribution
+    for k in 0..=d {
+        let m = 8 * k + j;
+        let r = pow_mod(16, d - k, m);
+        sum = sum.wrapping_add((((r as u128) << 64) / m as u128) as u64);
+        err += 1;
+    }
+
+    // Tail: 16^-(k-d) / (8k + j) until it drops below one unit
+    let mut k = d + 1;
+    loop {
+        let shift = 4 * (k - d);
+        if shift >= 64 {
+            break;
+        }
+        let term = (1u64 << (64 - shift)) / (8 * k + j);
+        if term == 0 {
+            break;
+        }
+        sum = sum.wrapping_add(term);
+        err += 1;
+        k += 1;
+    }
+
+    // The dropped remainder of the tail is below one unit
+    (sum, err + 1)
+}
+
+/// base^exp mod m.
+fn pow_mod(base: u64, mut exp: u64, m: u64) -> u64 {
+    if m == 1 {
+        return 0;
+    }
+    let mul = |a: u64, b: u64| -> u64 {
+        if m <= u32::MAX as u64 {
+            a * b % m
+        } else {
+            ((a as u128 * b as u128) % m as u128) as u64
+        }
+    };
+
+    let mut result = 1;
+    let mut base = base % m;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mul(result, base);
+        }
+        base = mul(base, base);
+        exp >>= 1;
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn known_hex_digits() {
+        assert_eq!(hex_digits_at(1, 8).unwrap(), "243F6A88");
+        assert_eq!(hex_digits_at(1_000_000, 14).unwrap(), "26C65E52CB4593");
+    }
+
+    #[test]
+    fn rejects_counts_out_of_range() {
+        assert!(hex_digits_at(1, usize::MAX).is_err());
+        assert!(hex_digits_at(MAX_POSITION, 2).is_err());
+        assert_eq!(hex_digits_at(1, 0).unwrap(), "");
+    }
+}
@@ -0,0 +1,120 @@
+//! Continued fractions and best rational approximations of a computed
+//! constant.
+//!
+//! A truncated result only pins the constant down to an interval one unit
+//! in the last digit wide: x ∈ [I / base^digits, (I + 1) / base^digits]
+//! (mirrored for negative values). Euclid's algorithm runs on both ends at
+//! once and a partial quotient is emitted only while the two agree, so
+//! every quotient returned is a quotient of x itself. Each one is worth
+//! about one decimal digit (Lévy's constant, 1.03 digits per quotient).
+//!
+//! Convergents p/q come with the classical bound
+//!
+//!   |x - p_n/q_n| < 1 / (q_n · q_{n+1})
+//!
+//! which needs the next quotient; the last convergent falls back to 1/q_n².
+
+use crate::PiResult;
+use rug::float::Round;
+use rug::integer::IntegerExt64;
+use rug::{Complete, Float, Integer};
+
+/// One convergent p/q of a continued fraction.
+#[derive(Debug, Clone)]
+pub struct Convergent {
+    pub p: Integer,
+    pub q: Integer,
+    /// Upper bound on |x - p/q|, rounded up; print it with `Round::Up`
+    /// to keep it a bound
+    pub error_bound: Float,
+}
+
+/// Partial quotients [a0; a1, a2, ...] of the constant in `result` that its
+/// digits guarantee, at most `limit` of them.
+pub fn partial_quotients(result: &PiResult, limit: Option<usize>) -> Vec<Integer> {
+    let scale = Integer::u64_pow_u64(u64::from(result.base), result.digits).complete();
+    // Truncation is toward zero, so the other end is one unit further out
+    let (lo, hi) = if result.integer < 0 {
+        (
+            Integer::from(&result.integer - 1u32),
+            result.integer.clone(),
+        )
+    } else {
+        (
+            result.integer.clone(),
+            Integer::from(&result.integer + 1u32),
+        )
+    };
+
+    let mut quotients = Vec::new();
+    let (mut n1, mut d1) = (lo, scale.clone());
+    let (mut n2, mut d2) = (hi, scale);
+    while limit.is_none_or(|limit| quotients.len() < limit) {
+        let (q1, r1) = <(Integer, Integer)>::from(n1.div_rem_floor_ref(&d1));
+        let (q2, r2) = <(Integer, Integer)>::from(n2.div_rem_floor_ref(&d2));
+        if q1 != q2 {
+            break;
+        }
+        quotients.push(q1);
+        // An exact end means x may be that rational; nothing more is certain
+        if r1 == 0 || r2 == 0 {
+            break;
+        }
+        (n1, d1) = (d1, r1);
+        (n2, d2) = (d2, r2);
+    }
+    quotients
+}
+
+/// Convergents of the continued fraction `quotients`, with error bounds.
+pub fn convergents(quotients: &[Integer]) -> Vec<Convergent> {
+    // p_n = a_n p_{n-1} + p_{n-2}, q_n = a_n q_{n-1} + q_{n-2}
+    let (mut p_prev, mut q_prev) = (Integer::from(0), Integer::from(1));
+    let (mut p, mut q) = (Integer::from(1), Integer::from(0));
+    let mut fractions = Vec::with_capacity(quotients.len());
+    for a in quotients {
+        let p_next = Integer::from(a * &p) + &p_prev;
+        let q_next = Integer::from(a * &q) + &q_prev;
+        (p_prev, q_prev) = (p, q);
+        (p, q) = (p_next, q_next);
+        fractions.push((p.clone(), q.clone()));
+    }
+
+    // Rounded up, so the bound still holds after conversion
+    let bound = |denominator: Integer| {
+        let (mut bound, _) = Float::with_val_round_64(64, denominator, Round::Down);
+        bound.recip_round(Round::Up);
+        bound
+    };
+    let mut result = Vec::with_capacity(fractions.len());
+    for i in 0..fractions.len() {
+        let q = &fractions[i].1;
+        let error_bound = match fractions.get(i + 1) {
+            Some((_, q_next)) => bound(Integer::from(q * q_next)),
+            None => bound(Integer::from(q.square_ref())),
+        };
+        let (p, q) = fractions[i].clone();
+        result.push(Convergent { p, q, error_bound });
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compute_pi;
+
+    #[test]
+    fn pi_quotients_and_convergents() {
+        let quotients = partial_quotients(&compute_pi(50).unwrap(), Some(5));
+        assert_eq!(quotients, [3, 7, 15, 1, 292]);
+
+        let last = convergents(&quotients).pop().unwrap();
+        assert_eq!(
+            (last.p, last.q),
+            (Integer::from(103993), Integer::from(33102))
+        );
+        // |π - 103993/33102| ≈ 5.8e-10, under 1/33102²
+        assert!(last.error_bound > 5.8e-10);
+    }
+}
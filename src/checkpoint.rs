@@ -0,0 +1,314 @@
+//! On-disk checkpoints for long binary-splitting runs.
+//!
+//! `binary_split_tracked` writes every finished subrange [a, b) of its top
+//! levels to `<dir>/split_<a>_<b>.bin` as its (P, Q, T) triple. Once a parent
+//! range is saved its children are deleted, so the directory stays roughly
+//! the size of the largest finished subtree. A restarted run loads whatever
+//! ranges exist and only recomputes the rest.
+//!
+//! `<dir>/checkpoint.txt` records the series, digits and terms the files
+//! belong to; a run with different parameters refuses to use the directory.
+//!
+//! Separately, `SeriesState` is the final (P, Q, T) of a finished run over
+//! [0, N) in a single file. A later run needing M > N terms loads it, splits
+//! only [N, M) and merges the two, instead of starting from term 0.
+
+use rug::Integer;
+use rug::integer::Order;
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+
+const MAGIC: &[u8; 8] = b"PIQT0001";
+const STATE_MAGIC: &[u8; 8] = b"PIST0001";
+
+/// A checkpoint directory bound to one (series, digits, terms) run.
+pub struct Checkpoint {
+    dir: PathBuf,
+}
+
+impl Checkpoint {
+    /// Open (or create) `dir` for a run of `digits` digits over `terms`
+    /// terms of the series named `series`.
+    ///
+    /// Fails if the directory already holds a checkpoint for other parameters.
+    pub fn open(dir: &Path, series: &str, digits: u64, terms: u64) -> Result<Checkpoint, String> {
+        fs::create_dir_all(dir)
+            .map_err(|e| format!("Cannot create checkpoint dir {}: {}", dir.display(), e))?;
+
+        let manifest = dir.join("checkpoint.txt");
+        let expected = format!("series={}\ndigits={}\nterms={}\n", series, digits, terms);
+        match fs::read_to_string(&manifest) {
+            Ok(found) if found == expected => {}
+            Ok(found) => {
+                return Err(format!(
+                    "Checkpoint dir {} belongs to another run ({}), this run has {}",
+                    dir.display(),
+                    found.trim().replace('\n', ", "),
+                    expected.trim().replace('\n', ", ")
+                ));
+            }
+            Err(_) => write_atomic(&manifest, |w| w.write_all(expected.as_bytes()))?,
+        }
+
+        Ok(Checkpoint {
+            dir: dir.to_path_buf(),
+        })
+    }
+
+    fn path(&self, a: u64, b: u64) -> PathBuf {
+        self.dir.join(format!("split_{}_{}.bin", a, b))
+    }
+
+    /// Load the saved triple for [a, b), if there is one.
+    pub fn load(&self, a: u64, b: u64) -> Result<Option<(Integer, Integer, Integer)>, String> {
+        let path = self.path(a, b);
+        let file = match File::open(&path) {
+            Ok(f) => f,
+            Err(_) => return Ok(None),
+        };
+        let err = |e: std::io::Error| format!("Corrupt checkpoint {}: {}", path.display(), e);
+        let mut r = BufReader::new(file);
+
+        let mut magic = [0u8; 8];
+        r.read_exact(&mut magic).map_err(err)?;
+        let range = (
+            read_u64(&mut r).map_err(err)?,
+            read_u64(&mut r).map_err(err)?,
+        );
+        if &magic != MAGIC || range != (a, b) {
+            return Err(format!("Corrupt checkpoint {}: bad header", path.display()));
+        }
+
+        let p = read_integer(&mut r).map_err(err)?;
+        let q = read_integer(&mut r).map_err(err)?;
+        let t = read_integer(&mut r).map_err(err)?;
+        Ok(Some((p, q, t)))
+    }
+
+    /// Save the triple for [a, b). The file appears atomically, so a run
+    /// killed mid-write never leaves a truncated checkpoint behind.
+    pub fn save(&self, a: u64, b: u64, pqt: &(Integer, Integer, Integer)) -> Result<(), String> {
+        let path = self.path(a, b);
+        write_atomic(&path, |w| {
+            w.write_all(MAGIC)?;
+            w.write_all(&a.to_le_bytes())?;
+            w.write_all(&b.to_le_bytes())?;
+            write_integer(w, &pqt.0)?;
+            write_integer(w, &pqt.1)?;
+            write_integer(w, &pqt.2)
+        })
+    }
+
+    /// Drop the saved file for [a, b); it is covered by a saved parent.
+    pub(crate) fn discard(&self, a: u64, b: u64) {
+        let _ = fs::remove_file(self.path(a, b));
+    }
+}
+
+/// P, Q, T over [0, terms) of the series named `series`.
+#[derive(Debug, Clone)]
+pub struct SeriesState {
+    pub series: String,
+    pub terms: u64,
+    pub pqt: (Integer, Integer, Integer),
+}
+
+impl SeriesState {
+    /// Save to `path`, atomically like the checkpoint files.
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        write_atomic(path, |w| {
+            w.write_all(STATE_MAGIC)?;
+            w.write_all(&(self.series.len() as u64).to_le_bytes())?;
+            w.write_all(self.series.as_bytes())?;
+            w.write_all(&self.terms.to_le_bytes())?;
+            write_integer(w, &self.pqt.0)?;
+            write_integer(w, &self.pqt.1)?;
+            write_integer(w, &self.pqt.2)
+        })
+    }
+
+    /// Load a state written by `save`.
+    pub fn load(path: &Path) -> Result<SeriesState, String> {
+        let file =
+            File::open(path).map_err(|e| format!("Cannot read state {}: {}", path.display(), e))?;
+        let err = |e: std::io::Error| format!("Corrupt state {}: {}", path.display(), e);
+        let mut r = BufReader::new(file);
+
+        let mut magic = [0u8; 8];
+        r.read_exact(&mut magic).map_err(err)?;
+        if &magic != STATE_MAGIC {
+            return Err(format!("Corrupt state {}: bad header", path.display()));
+        }
+        let len = read_u64(&mut r).map_err(err)?;
+        let mut name = Vec::new();
+        (&mut r).take(len).read_to_end(&mut name).map_err(err)?;
+        let series = String::from_utf8(name)
+            .map_err(|_| format!("Corrupt state {}: bad series name", path.display()))?;
+        let terms = read_u64(&mut r).map_err(err)?;
+
+        let p = read_integer(&mut r).map_err(err)?;
+        let q = read_integer(&mut r).map_err(err)?;
+        let t = read_integer(&mut r).map_err(err)?;
+        Ok(SeriesState {
+            series,
+            terms,
+            pqt: (p, q, t),
+        })
+    }
+}
+
+/// Write `path` via a temporary file and a rename.
+fn write_atomic(
+    path: &Path,
+    contents: impl FnOnce(&mut BufWriter<File>) -> std::io::Result<()>,
+) -> Result<(), String> {
+    let tmp = path.with_extension("tmp");
+    let err = |e: std::io::Error| format!("Cannot write checkpoint {}: {}", path.display(), e);
+
+    let mut w = BufWriter::new(File::create(&tmp).map_err(err)?);
+    contents(&mut w).map_err(err)?;
+    w.into_inner()
+        .map_err(|e| err(e.into_error()))?
+        .sync_all()
+        .map_err(err)?;
+    fs::rename(&tmp, path).map_err(err)
+}
+
+/// Sign byte, little-endian byte count, then magnitude bytes (least
+/// significant first).
+fn write_integer(w: &mut impl Write, n: &Integer) -> std::io::Result<()> {
+    let digits = n.to_digits::<u8>(Order::Lsf);
+    w.write_all(&[u8::from(n.is_negative())])?;
+    w.write_all(&(digits.len() as u64).to_le_bytes())?;
+    w.write_all(&digits)
+}
+
+fn read_integer(r: &mut impl Read) -> std::io::Result<Integer> {
+    let mut sign = [0u8; 1];
+    r.read_exact(&mut sign)?;
+    let len = read_u64(r)?;
+    let mut digits = Vec::new();
+    r.take(len).read_to_end(&mut digits)?;
+    if digits.len() as u64 != len {
+        return Err(std::io::ErrorKind::UnexpectedEof.into());
+    }
+
+    let n = Integer::from_digits(&digits, Order::Lsf);
+    Ok(if sign[0] == 1 { -n } else { n })
+}
+
+fn read_u64(r: &mut impl Read) -> std::io::Result<u64> {
+    let mut bytes = [0u8; 8];
+    r.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hypergeometric::split;
+    use crate::series::Series;
+    use crate::split::{Tracking, binary_split_tracked};
+    use crate::{ComputeOptions, split_tracked};
+
+    /// A fresh directory under the system temp dir for one test.
+    fn scratch(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("pi_calculator_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn triple() -> (Integer, Integer, Integer) {
+        (
+            Integer::from(-3) << 200u32,
+            Integer::from(0x1234_5678u32),
+            -(Integer::from(7) << 1000u32) - 1u32,
+        )
+    }
+
+    #[test]
+    fn checkpoint_round_trip() {
+        let dir = scratch("checkpoint_round_trip");
+        let ckpt = Checkpoint::open(&dir, "chudnovsky", 100, 10).unwrap();
+        ckpt.save(0, 5, &triple()).unwrap();
+
+        assert_eq!(ckpt.load(0, 5).unwrap(), Some(triple()));
+        assert_eq!(ckpt.load(5, 10).unwrap(), None);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn state_round_trip() {
+        let dir = scratch("state_round_trip");
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("pi.state");
+        let state = SeriesState {
+            series: "chudnovsky".to_string(),
+            terms: 42,
+            pqt: triple(),
+        };
+        state.save(&path).unwrap();
+
+        let loaded = SeriesState::load(&path).unwrap();
+        assert_eq!(loaded.series, "chudnovsky");
+        assert_eq!(loaded.terms, 42);
+        assert_eq!(loaded.pqt, triple());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn extended_split_matches_full_run() {
+        let dir = scratch("extended_split");
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("pi.state");
+        let series = Series::CHUDNOVSKY;
+        let full = split(&series, 0, 1000);
+
+        let save = ComputeOptions {
+            save_state: Some(path.clone()),
+            ..ComputeOptions::default()
+        };
+        split_tracked(&series, series.name, 7000, 400, &save).unwrap();
+
+        for threads in [1, 4] {
+            let extend = ComputeOptions {
+                threads,
+                extend_from: Some(path.clone()),
+                ..ComputeOptions::default()
+            };
+            let extended = split_tracked(&series, series.name, 14000, 1000, &extend).unwrap();
+            assert_eq!(extended, full);
+        }
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn mismatched_manifest_is_rejected() {
+        let dir = scratch("mismatched_manifest");
+        Checkpoint::open(&dir, "chudnovsky", 100, 10).unwrap();
+
+        assert!(Checkpoint::open(&dir, "chudnovsky", 100, 10).is_ok());
+        assert!(Checkpoint::open(&dir, "chudnovsky", 200, 10).is_err());
+        assert!(Checkpoint::open(&dir, "ramanujan", 100, 10).is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn resumed_split_matches_serial() {
+        let dir = scratch("resumed_split");
+        let series = Series::CHUDNOVSKY;
+        let terms = 1000;
+        let ckpt = Checkpoint::open(&dir, series.name, 14000, terms).unwrap();
+        let tracking = Tracking::new(&series, terms, Some(&ckpt), None);
+
+        // An interrupted run that only finished the left half
+        binary_split_tracked(0, terms / 2, 1, &tracking).unwrap();
+        assert!(ckpt.load(0, terms / 2).unwrap().is_some());
+
+        let resumed = binary_split_tracked(0, terms, 2, &tracking).unwrap();
+        assert_eq!(resumed, split(&series, 0, terms));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
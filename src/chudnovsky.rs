@@ -0,0 +1,32 @@
+use crate::hypergeometric::{Hypergeometric, split, split_parallel};
+use crate::series::Series;
+use rug::Integer;
+
+pub use crate::hypergeometric::PARALLEL_CUTOFF;
+
+/// Number of series terms needed for `digits` decimal digits.
+///
+/// Each Chudnovsky term yields ~14.18 digits; one extra term covers the
+/// truncated remainder.
+pub fn terms_for_digits(digits: u64) -> u64 {
+    Series::CHUDNOVSKY.terms_for_digits(digits)
+}
+
+/// Estimated size in bits of Q(0, N) (T(0, N) is about the same):
+/// each term contributes log2(k^3 * C^3 / 24) ≈ 3 log2(k) + 53.3 bits.
+pub fn series_bits(terms: u64) -> u64 {
+    Series::CHUDNOVSKY.series_bits(terms)
+}
+
+/// Binary splitting for the Chudnovsky series
+///
+/// We compute P(a, b), Q(a, b), T(a, b) such that:
+///   π = (Q(0, N) * 426880 * sqrt(10005)) / T(0, N)
+pub fn binary_split(a: u64, b: u64) -> (Integer, Integer, Integer) {
+    split(&Series::CHUDNOVSKY, a, b)
+}
+
+/// Multithreaded `binary_split`; see `hypergeometric::split_parallel`.
+pub fn binary_split_parallel(a: u64, b: u64, threads: usize) -> (Integer, Integer, Integer) {
+    split_parallel(&Series::CHUDNOVSKY, a, b, threads)
+}
@@ -0,0 +1,549 @@
+//! Constants other than π.
+//!
+//! Each constant is evaluated to a `Float` of the requested precision and
+//! then goes through the same truncation and output path as π, so every
+//! option that shapes the output (`--base`, `--output`, `--report`, ...)
+//! applies unchanged.
+
+use crate::hypergeometric::Hypergeometric;
+use crate::progress::Phase;
+use crate::{
+    Algorithm, ComputeOptions, agm, algebraic, chudnovsky_pi, gamma, log, machin,
+    precision_for_digits, split_tracked,
+};
+use rug::ops::Pow;
+use rug::{Float, Integer};
+use std::fmt;
+
+/// A constant that can be computed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Constant {
+    #[default]
+    Pi,
+    /// Euler's number, Σ 1/k!
+    E,
+    /// Natural logarithm of 2
+    Ln2,
+    /// Natural logarithm of 10
+    Ln10,
+    /// ln(p/q) for positive integers p and q
+    Ln(u64, u64),
+    /// Apéry's constant ζ(3)
+    Zeta3,
+    /// Catalan's constant G
+    Catalan,
+    /// Euler–Mascheroni constant γ
+    Gamma,
+    /// Square root of a non-negative integer
+    Sqrt(u64),
+    /// Cube root of a non-negative integer
+    Cbrt(u64),
+    /// Golden ratio φ = (1 + √5) / 2
+    Phi,
+    /// Lemniscate constant ϖ = π / AGM(1, √2)
+    Lemniscate,
+    /// Γ(1/4)
+    GammaQuarter,
+    /// τ = 2π
+    Tau,
+    /// 1/π
+    InvPi,
+    /// π²
+    PiSquared,
+    /// √π
+    SqrtPi,
+    /// π/2
+    HalfPi,
+    /// ln π
+    LnPi,
+}
+
+/// Extra decimal digits of π computed for the constants derived from it,
+/// so that rounding in the final operation stays below the truncated digits.
+const DERIVED_GUARD_DIGITS: u64 = 20;
+
+impl Constant {
+    /// Parse a `--constant` argument.
+    pub fn parse(spec: &str) -> Result<Constant, String> {
+        match spec.to_ascii_lowercase().as_str() {
+            "pi" | "π" => Ok(Constant::Pi),
+            "e" => Ok(Constant::E),
+            "ln2" => Ok(Constant::Ln2),
+            "ln10" => Ok(Constant::Ln10),
+            "zeta3" | "apery" => Ok(Constant::Zeta3),
+            "catalan" => Ok(Constant::Catalan),
+            "gamma" | "euler" => Ok(Constant::Gamma),
+            "phi" | "golden" => Ok(Constant::Phi),
+            "lemniscate" => Ok(Constant::Lemniscate),
+            "gamma_quarter" => Ok(Constant::GammaQuarter),
+            "tau" | "τ" | "2pi" => Ok(Constant::Tau),
+            "inv_pi" | "1/pi" => Ok(Constant::InvPi),
+            "pi2" | "pi^2" => Ok(Constant::PiSquared),
+            "sqrt_pi" => Ok(Constant::SqrtPi),
+            "half_pi" | "pi/2" => Ok(Constant::HalfPi),
+            "ln_pi" => Ok(Constant::LnPi),
+            name => {
+                if let Some(ratio) = name.strip_prefix("ln:") {
+                    return parse_ratio(ratio).map(|(p, q)| Constant::Ln(p, q));
+                }
+                if let Some(n) = name.strip_prefix("sqrt:") {
+                    return parse_radicand(n).map(Constant::Sqrt);
+                }
+                if let Some(n) = name.strip_prefix("cbrt:") {
+                    return parse_radicand(n).map(Constant::Cbrt);
+                }
+                Err(format!(
+                    "Unknown constant \"{}\", expected pi, e, ln2, ln10, ln:P/Q, zeta3, catalan, gamma, sqrt:N, cbrt:N, phi, lemniscate, gamma_quarter, tau, inv_pi, pi2, sqrt_pi, half_pi or ln_pi",
+                    spec
+                ))
+            }
+        }
+    }
+
+    /// Name as accepted by `--constant`.
+    pub fn name(self) -> String {
+        match self {
+            Constant::Pi => "pi".to_string(),
+            Constant::E => "e".to_string(),
+            Constant::Ln2 => "ln2".to_string(),
+            Constant::Ln10 => "ln10".to_string(),
+            Constant::Ln(p, 1) => format!("ln:{}", p),
+            Constant::Ln(p, q) => format!("ln:{}/{}", p, q),
+            Constant::Zeta3 => "zeta3".to_string(),
+            Constant::Catalan => "catalan".to_string(),
+            Constant::Gamma => "gamma".to_string(),
+            Constant::Sqrt(n) => format!("sqrt:{}", n),
+            Constant::Cbrt(n) => format!("cbrt:{}", n),
+            Constant::Phi => "phi".to_string(),
+            Constant::Lemniscate => "lemniscate".to_string(),
+            Constant::GammaQuarter => "gamma_quarter".to_string(),
+            Constant::Tau => "tau".to_string(),
+            Constant::InvPi => "inv_pi".to_string(),
+            Constant::PiSquared => "pi2".to_string(),
+            Constant::SqrtPi => "sqrt_pi".to_string(),
+            Constant::HalfPi => "half_pi".to_string(),
+            Constant::LnPi => "ln_pi".to_string(),
+        }
+    }
+
+    /// Symbol for messages.
+    pub fn symbol(self) -> String {
+        match self {
+            Constant::Pi => "π".to_string(),
+            Constant::E => "e".to_string(),
+            Constant::Ln2 => "ln 2".to_string(),
+            Constant::Ln10 => "ln 10".to_string(),
+            Constant::Ln(p, 1) => format!("ln {}", p),
+            Constant::Ln(p, q) => format!("ln({}/{})", p, q),
+            Constant::Zeta3 => "ζ(3)".to_string(),
+            Constant::Catalan => "G".to_string(),
+            Constant::Gamma => "γ".to_string(),
+            Constant::Sqrt(n) => format!("√{}", n),
+            Constant::Cbrt(n) => format!("∛{}", n),
+            Constant::Phi => "φ".to_string(),
+            Constant::Lemniscate => "ϖ".to_string(),
+            Constant::GammaQuarter => "Γ(1/4)".to_string(),
+            Constant::Tau => "τ".to_string(),
+            Constant::InvPi => "1/π".to_string(),
+            Constant::PiSquared => "π²".to_string(),
+            Constant::SqrtPi => "√π".to_string(),
+            Constant::HalfPi => "π/2".to_string(),
+            Constant::LnPi => "ln π".to_string(),
+        }
+    }
+
+    /// How the constant is computed, for messages and reports (π depends on
+    /// the algorithm and is described by `Algorithm`).
+    pub fn method(self) -> &'static str {
+        match self {
+            Constant::Pi => "series",
+            Constant::E => "Σ 1/k!",
+            Constant::Ln2 | Constant::Ln10 | Constant::Ln(..) => "Machin-like atanh",
+            Constant::Zeta3 => "Amdeberhan–Zeilberger series",
+            Constant::Catalan => "Pilehrood series",
+            Constant::Gamma => "Brent–McMillan",
+            Constant::Sqrt(_) | Constant::Cbrt(_) | Constant::Phi => "Newton iteration",
+            Constant::Lemniscate | Constant::GammaQuarter => "Chudnovsky + AGM",
+            Constant::Tau
+            | Constant::InvPi
+            | Constant::PiSquared
+            | Constant::SqrtPi
+            | Constant::HalfPi
+            | Constant::LnPi => "Chudnovsky π",
+        }
+    }
+}
+
+impl fmt::Display for Constant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.symbol())
+    }
+}
+
+/// Estimated size in bits of the largest integers computing `constant` to
+/// `decimal_digits` digits takes, for the up-front size check; 0 when they
+/// stay near the result's precision.
+pub(crate) fn integer_bits(
+    constant: Constant,
+    decimal_digits: u64,
+    options: &ComputeOptions,
+) -> u64 {
+    let pi_series = |digits| {
+        options
+            .series
+            .series_bits(options.series.terms_for_digits(digits))
+    };
+    match constant {
+        Constant::Pi => match options.algorithm {
+            Algorithm::Chudnovsky => pi_series(decimal_digits),
+            Algorithm::Agm => 0,
+            Algorithm::Machin => machin::series_bits(&options.formula, decimal_digits),
+        },
+        Constant::Lemniscate | Constant::GammaQuarter => pi_series(decimal_digits),
+        Constant::Gamma => gamma::series_bits(decimal_digits),
+        Constant::Tau
+        | Constant::InvPi
+        | Constant::PiSquared
+        | Constant::SqrtPi
+        | Constant::HalfPi
+        | Constant::LnPi => pi_series(decimal_digits + DERIVED_GUARD_DIGITS),
+        Constant::E => ESeries.series_bits(e_terms_for_digits(decimal_digits)),
+        Constant::Zeta3 => Zeta3Series.series_bits(Zeta3Series::terms_for_digits(decimal_digits)),
+        Constant::Catalan => {
+            CatalanSeries.series_bits(CatalanSeries::terms_for_digits(decimal_digits))
+        }
+        Constant::Ln2 => log::series_bits(log::LN2, decimal_digits),
+        Constant::Ln10 => log::series_bits(log::LN10, decimal_digits),
+        Constant::Ln(p, q) => log::ln_ratio_bits(p, q, decimal_digits),
+        Constant::Sqrt(_) | Constant::Cbrt(_) | Constant::Phi => 0,
+    }
+}
+
+/// Evaluate `constant` (not π) to `precision` bits, good for
+/// `decimal_digits` decimal digits, and return it with the number of terms
+/// summed. Leaves the division phase running for the caller's truncation.
+pub(crate) fn evaluate(
+    constant: Constant,
+    digits: u64,
+    decimal_digits: u64,
+    precision: u64,
+    options: &ComputeOptions,
+    enter: &mut impl FnMut(Phase),
+) -> Result<(Float, u64), String> {
+    match constant {
+        Constant::Pi => unreachable!("π is computed by compute_constant"),
+        Constant::E => {
+            enter(Phase::Series);
+            let terms = e_terms_for_digits(decimal_digits);
+            let (_p, q, t) = split_tracked(&ESeries, "e", digits, terms, options)?;
+
+            enter(Phase::Division);
+            let e = Float::with_val_64(precision, &t) / Float::with_val_64(precision, &q);
+            Ok((e, terms))
+        }
+        Constant::Ln2 | Constant::Ln10 | Constant::Ln(..) => {
+            no_checkpoint(options)?;
+            enter(Phase::Series);
+            let threads = options.threads;
+            let ln = match constant {
+                Constant::Ln2 => log::atanh_sum(log::LN2, decimal_digits, precision, threads),
+                Constant::Ln10 => log::atanh_sum(log::LN10, decimal_digits, precision, threads),
+                Constant::Ln(p, q) => log::ln_ratio(p, q, decimal_digits, precision, threads),
+                _ => unreachable!(),
+            };
+            enter(Phase::Division);
+            Ok(ln)
+        }
+        Constant::Zeta3 | Constant::Catalan => {
+            // value = T / (den · Q)
+            let (series, name, den, terms): (&dyn Hypergeometric, _, u32, _) = match constant {
+                Constant::Zeta3 => (
+                    &Zeta3Series,
+                    "zeta3",
+                    64,
+                    Zeta3Series::terms_for_digits(decimal_digits),
+                ),
+                _ => (
+                    &CatalanSeries,
+                    "catalan",
+                    2,
+                    CatalanSeries::terms_for_digits(decimal_digits),
+                ),
+            };
+            enter(Phase::Series);
+            let (_p, q, t) = split_tracked(series, name, digits, terms, options)?;
+
+            enter(Phase::Division);
+            let q = Float::with_val_64(precision, q * den);
+            Ok((Float::with_val_64(precision, &t) / q, terms))
+        }
+        Constant::Gamma => {
+            no_checkpoint(options)?;
+            enter(Phase::Series);
+            let gamma = gamma::gamma(decimal_digits, precision, options.threads);
+            enter(Phase::Division);
+            Ok(gamma)
+        }
+        Constant::Sqrt(_) | Constant::Cbrt(_) | Constant::Phi => {
+            no_checkpoint(options)?;
+            enter(Phase::Sqrt);
+            let root = match constant {
+                Constant::Sqrt(n) => algebraic::root(n, 2, precision),
+                Constant::Cbrt(n) => algebraic::root(n, 3, precision),
+                _ => algebraic::phi(precision),
+            };
+            enter(Phase::Division);
+            Ok(root)
+        }
+        Constant::Lemniscate | Constant::GammaQuarter => {
+            // π from the Chudnovsky series, so checkpoints apply to it
+            let (pi, terms) = chudnovsky_pi(digits, decimal_digits, precision, options, enter)?;
+
+            enter(Phase::Agm);
+            let (value, steps) = match constant {
+                Constant::Lemniscate => agm::lemniscate(&pi, precision),
+                _ => agm::gamma_quarter(&pi, precision),
+            };
+            enter(Phase::Division);
+            Ok((value, terms + steps))
+        }
+        Constant::Tau
+        | Constant::InvPi
+        | Constant::PiSquared
+        | Constant::SqrtPi
+        | Constant::HalfPi
+        | Constant::LnPi => {
+            let guarded_digits = decimal_digits + DERIVED_GUARD_DIGITS;
+            let guarded = precision_for_digits(guarded_digits);
+            let (pi, terms) = chudnovsky_pi(digits, guarded_digits, guarded, options, enter)?;
+
+            let value = match constant {
+                Constant::Tau => pi * 2u32,
+                Constant::InvPi => pi.recip(),
+                Constant::PiSquared => pi.square(),
+                Constant::SqrtPi => pi.sqrt(),
+                Constant::HalfPi => pi / 2u32,
+                _ => pi.ln(),
+            };
+            Ok((Float::with_val_64(precision, &value), terms))
+        }
+    }
+}
+
+/// Checkpoints and saved states cover a single binary-splitting run;
+/// constants built from several sums do not support them.
+fn no_checkpoint(options: &ComputeOptions) -> Result<(), String> {
+    match options.split_only_flag() {
+        Some(flag) => Err(format!("{} is not supported for this constant", flag)),
+        None => Ok(()),
+    }
+}
+
+/// Parse the N of "sqrt:N" or "cbrt:N".
+fn parse_radicand(spec: &str) -> Result<u64, String> {
+    spec.trim().parse::<u64>().map_err(|_| {
+        format!(
+            "Invalid radicand \"{}\", expected a non-negative integer",
+            spec
+        )
+    })
+}
+
+/// Parse "P/Q" or "P" into positive integers.
+fn parse_ratio(spec: &str) -> Result<(u64, u64), String> {
+    let (p, q) = spec.split_once('/').unwrap_or((spec, "1"));
+    let parse = |s: &str| {
+        s.trim()
+            .parse::<u64>()
+            .ok()
+            .filter(|&n| n > 0)
+            .ok_or_else(|| format!("Invalid ratio \"{}\", expected positive integers P/Q", spec))
+    };
+    Ok((parse(p)?, parse(q)?))
+}
+
+/// e = Σ_k 1/k!: p(k) = 1, q(k) = k (q(0) = 1), a(k) = 1.
+#[derive(Debug, Clone, Copy)]
+pub struct ESeries;
+
+impl Hypergeometric for ESeries {
+    fn p(&self, _k: u64) -> Integer {
+        Integer::from(1)
+    }
+
+    fn q(&self, k: u64) -> Integer {
+        Integer::from(k.max(1))
+    }
+
+    fn a(&self, _k: u64) -> Integer {
+        Integer::from(1)
+    }
+
+    fn term_bits(&self, k: u64) -> f64 {
+        (k.max(2) as f64).log2()
+    }
+}
+
+/// Amdeberhan–Zeilberger:
+///   ζ(3) = 1/64 Σ_k (-1)^k (k!)^10 (205k² + 250k + 77) / ((2k+1)!)^5
+/// with term ratio -k^5 / (32 (2k+1)^5), about 3 digits per term.
+#[derive(Debug, Clone, Copy)]
+pub struct Zeta3Series;
+
+impl Zeta3Series {
+    /// Number of terms for `digits` decimal digits: log10(1024) per term.
+    pub fn terms_for_digits(digits: u64) -> u64 {
+        (digits as f64 / 1024f64.log10()) as u64 + 2
+    }
+}
+
+impl Hypergeometric for Zeta3Series {
+    fn p(&self, k: u64) -> Integer {
+        if k == 0 {
+            return Integer::from(1);
+        }
+        -Integer::from(k).pow(5)
+    }
+
+    fn q(&self, k: u64) -> Integer {
+        if k == 0 {
+            return Integer::from(1);
+        }
+        Integer::from(2 * k + 1).pow(5) * 32u32
+    }
+
+    fn a(&self, k: u64) -> Integer {
+        (Integer::from(205) * k + 250) * k + 77
+    }
+}
+
+/// Pilehrood:
+///   G = 1/2 Σ_k (-8)^k (3k + 2) / ((2k+1)³ binomial(2k, k)³)
+/// with term ratio -k³ / (2k+1)³, about 0.9 digits per term.
+#[derive(Debug, Clone, Copy)]
+pub struct CatalanSeries;
+
+impl CatalanSeries {
+    /// Number of terms for `digits` decimal digits: log10(8) per term.
+    pub fn terms_for_digits(digits: u64) -> u64 {
+        (digits as f64 / 8f64.log10()) as u64 + 2
+    }
+}
+
+impl Hypergeometric for CatalanSeries {
+    fn p(&self, k: u64) -> Integer {
+        if k == 0 {
+            return Integer::from(1);
+        }
+        -Integer::from(k).pow(3)
+    }
+
+    fn q(&self, k: u64) -> Integer {
+        if k == 0 {
+            return Integer::from(1);
+        }
+        Integer::from(2 * k + 1).pow(3)
+    }
+
+    fn a(&self, k: u64) -> Integer {
+        Integer::from(3 * k + 2)
+    }
+}
+
+/// Number of terms of Σ 1/k! for `digits` decimal digits.
+///
+/// The remainder after N terms is below 2/N!, so N must satisfy
+/// ln N! ≥ digits · ln 10. Stirling gives ln N! ≈ N ln N - N; that is
+/// inverted with Newton's method, and two extra terms cover the rest.
+pub fn e_terms_for_digits(digits: u64) -> u64 {
+    let target = (digits as f64 * std::f64::consts::LN_10).max(2.0);
+    let mut n = target / target.ln();
+    for _ in 0..16 {
+        n = (n - (n * n.ln() - n - target) / n.ln()).max(2.0);
+    }
+    n.ceil() as u64 + 2
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compute_constant;
+
+    /// `constant` to 50 decimals, as printed.
+    fn digits(constant: Constant) -> String {
+        compute_constant(constant, 50, &ComputeOptions::default())
+            .unwrap()
+            .to_digit_string()
+    }
+
+    #[test]
+    fn e_digits() {
+        assert_eq!(
+            digits(Constant::E),
+            "2.71828182845904523536028747135266249775724709369995"
+        );
+    }
+
+    #[test]
+    fn zeta3_and_catalan_digits() {
+        assert_eq!(
+            digits(Constant::Zeta3),
+            "1.20205690315959428539973816151144999076498629234049"
+        );
+        assert_eq!(
+            digits(Constant::Catalan),
+            "0.91596559417721901505460351493238411077414937428167"
+        );
+    }
+
+    #[test]
+    fn derived_digits() {
+        let expected = [
+            (
+                Constant::Tau,
+                "6.28318530717958647692528676655900576839433879875021",
+            ),
+            (
+                Constant::InvPi,
+                "0.31830988618379067153776752674502872406891929148091",
+            ),
+            (
+                Constant::PiSquared,
+                "9.86960440108935861883449099987615113531369940724079",
+            ),
+            (
+                Constant::SqrtPi,
+                "1.77245385090551602729816748334114518279754945612238",
+            ),
+            (
+                Constant::HalfPi,
+                "1.57079632679489661923132169163975144209858469968755",
+            ),
+            (
+                Constant::LnPi,
+                "1.14472988584940017414342735135305871164729481291531",
+            ),
+        ];
+        for (constant, value) in expected {
+            assert_eq!(digits(constant), value, "{}", constant);
+        }
+    }
+
+    #[test]
+    fn oversized_runs_are_rejected_up_front() {
+        let machin = ComputeOptions {
+            algorithm: Algorithm::Machin,
+            ..ComputeOptions::default()
+        };
+        let agm = ComputeOptions {
+            algorithm: Algorithm::Agm,
+            ..ComputeOptions::default()
+        };
+        let digits = 5_000_000_000;
+
+        assert!(compute_constant(Constant::Ln2, digits, &agm).is_err());
+        assert!(compute_constant(Constant::Ln(1_000_000_007, 700_000_001), digits, &agm).is_err());
+        assert!(compute_constant(Constant::Pi, digits, &machin).is_err());
+        assert_eq!(integer_bits(Constant::Pi, digits, &agm), 0);
+    }
+}
@@ -0,0 +1,88 @@
+/// Parse a digit specification like:
+/// "123", "1K", "10M", "2g", "132876K", "1e6", "3E7"
+///
+/// Supports suffixes:
+///   K = 1_000 (10^3)
+///   M = 1_000_000 (10^6)
+///   G = 1_000_000_000 (10^9)
+///   T = 1_000_000_000_000 (10^12)
+///
+/// Also supports scientific notation: "<int>e<int>", e.g. "1e6".
+///
+/// Returns number of digits as u64. Whether that many digits can actually
+/// be computed is checked separately by `check_digits`.
+pub fn parse_digit_spec(spec: &str) -> Result<u64, String> {
+    let s = spec.trim();
+    if s.is_empty() {
+        return Err("Empty digits specification".to_string());
+    }
+
+    // 1) Scientific notation: "<int>e<int>" or "<int>E<int>"
+    if let Some(pos) = s.find(['e', 'E']) {
+        let (mantissa_str, exp_str_with_e) = s.split_at(pos);
+        let exp_str = &exp_str_with_e[1..]; // skip 'e' or 'E'
+
+        if mantissa_str.is_empty() || exp_str.is_empty() {
+            return Err(format!("Invalid scientific notation: \"{}\"", spec));
+        }
+
+        let mantissa: u64 = mantissa_str
+            .parse()
+            .map_err(|_| format!("Invalid mantissa in \"{}\"", spec))?;
+        let exp: u32 = exp_str
+            .parse()
+            .map_err(|_| format!("Invalid exponent in \"{}\"", spec))?;
+
+        let multiplier = 10u64
+            .checked_pow(exp)
+            .ok_or_else(|| format!("Exponent too large in \"{}\"", spec))?;
+
+        let value = mantissa
+            .checked_mul(multiplier)
+            .ok_or_else(|| format!("Digits value overflow for \"{}\"", spec))?;
+
+        return Ok(value);
+    }
+
+    // 2) Suffix-based notation: K, M, G, T
+    let s_upper = s.to_uppercase();
+    let (num_str, multiplier): (&str, u64) = if s_upper.ends_with('K') {
+        (&s_upper[..s_upper.len() - 1], 1_000)
+    } else if s_upper.ends_with('M') {
+        (&s_upper[..s_upper.len() - 1], 1_000_000)
+    } else if s_upper.ends_with('G') {
+        (&s_upper[..s_upper.len() - 1], 1_000_000_000)
+    } else if s_upper.ends_with('T') {
+        (&s_upper[..s_upper.len() - 1], 1_000_000_000_000)
+    } else {
+        (s_upper.as_str(), 1)
+    };
+
+    if num_str.is_empty() {
+        return Err(format!("Missing number before suffix in \"{}\"", spec));
+    }
+
+    let base: u64 = num_str
+        .parse()
+        .map_err(|_| format!("Invalid number part in \"{}\"", spec))?;
+
+    let value: u64 = base
+        .checked_mul(multiplier)
+        .ok_or_else(|| format!("Digits value overflow for \"{}\"", spec))?;
+
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_counts_beyond_u32() {
+        assert_eq!(parse_digit_spec("5T"), Ok(5_000_000_000_000));
+        assert_eq!(parse_digit_spec("5G"), Ok(5_000_000_000));
+        assert_eq!(parse_digit_spec("1e19"), Ok(10_000_000_000_000_000_000));
+        assert!(parse_digit_spec("1e20").is_err());
+        assert!(parse_digit_spec("20000000T").is_err());
+    }
+}
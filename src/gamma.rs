@@ -0,0 +1,208 @@
+//! Euler–Mascheroni constant by the Brent–McMillan algorithm.
+//!
+//!   A = Σ_k (n^k / k!)² H_k,   B = Σ_k (n^k / k!)²,   γ = A/B - ln n + O(e^-4n)
+//!
+//! with H_k the k-th harmonic number. Terms of B shrink like 1/(2k)! ·
+//! (2n)^2k, so summing k <= α·n with α(ln α - 1) = 1 (α ≈ 3.5911) leaves a
+//! tail below e^-4n as well.
+//!
+//! Both sums are evaluated together by binary splitting. Besides P, Q, T of
+//! the plain hypergeometric series (p(k) = n², q(k) = k²), every range
+//! [a, b) carries
+//!
+//!   D(a, b) = Π_{a<=j<b} j,   C(a, b) / D(a, b) = Σ_{a<=j<b} 1/j
+//!   V(a, b) / (D·Q) = Σ_{a<=k<b} Π_{a<=j<=k} p(j)/q(j) · Σ_{a<=j<=k} 1/j
+//!
+//! so that A = V(1, K+1) / (D·Q) and B = 1 + T(1, K+1) / Q.
+
+use crate::hypergeometric::PARALLEL_CUTOFF;
+use crate::log;
+use rug::{Float, Integer};
+use std::thread;
+
+/// α with α(ln α - 1) = 1: the number of terms per unit of n.
+const ALPHA: f64 = 3.591_121_476_668_622;
+
+/// Binary splitting state for a range of terms; see the module docs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sums {
+    pub p: Integer,
+    pub q: Integer,
+    pub d: Integer,
+    pub c: Integer,
+    pub t: Integer,
+    pub v: Integer,
+}
+
+/// n for `digits` decimal digits: e^-4n must be below 10^-digits.
+pub fn n_for_digits(digits: u64) -> u64 {
+    (digits as f64 * std::f64::consts::LN_10 / 4.0) as u64 + 2
+}
+
+/// Number of terms summed for a given n.
+pub fn terms_for_n(n: u64) -> u64 {
+    (ALPHA * n as f64) as u64 + 1
+}
+
+/// Estimated size in bits of the largest integers for `digits` decimal
+/// digits: Q(1, K+1) = (K!)² has about 2K·log2 K bits, and V and the final
+/// D·(Q + T) carry another D = K!, about 3K·log2 K in all.
+pub fn series_bits(digits: u64) -> u64 {
+    let terms = terms_for_n(n_for_digits(digits)) as f64;
+    (3.0 * terms * terms.log2()) as u64
+}
+
+/// Binary splitting of the sums for `n` over terms [a, b), a >= 1. An
+/// empty range gives the identity of `merge` (P = Q = D = 1, C = T = V = 0).
+///
+/// Panics if `b < a`.
+pub fn split(n: u64, a: u64, b: u64) -> Sums {
+    assert!(a <= b, "binary splitting range [{}, {}) is reversed", a, b);
+    if a == b {
+        Sums {
+            p: Integer::from(1),
+            q: Integer::from(1),
+            d: Integer::from(1),
+            c: Integer::from(0),
+            t: Integer::from(0),
+            v: Integer::from(0),
+        }
+    } else if b - a == 1 {
+        // Base case: term a contributes n²/a² to T and n²/a² · 1/a to V
+        let n2 = Integer::from(n).square();
+        Sums {
+            q: Integer::from(a).square(),
+            d: Integer::from(a),
+            c: Integer::from(1),
+            t: n2.clone(),
+            v: n2.clone(),
+            p: n2,
+        }
+    } else {
+        let m = (a + b) / 2;
+        merge(split(n, a, m), split(n, m, b))
+    }
+}
+
+/// Merge two adjacent subranges [a, m) and [m, b):
+///   P = P1·P2,  Q = Q1·Q2,  D = D1·D2
+///   C = C1·D2 + D1·C2
+///   T = Q2·T1 + P1·T2
+///   V = D2·Q2·V1 + P1·(C1·D2·T2 + D1·V2)
+pub fn merge(left: Sums, right: Sums) -> Sums {
+    let Sums {
+        p: mut p1,
+        q: mut q1,
+        d: mut d1,
+        c: c1,
+        t: t1,
+        v: v1,
+    } = left;
+    let Sums {
+        p: p2,
+        q: q2,
+        d: d2,
+        c: c2,
+        t: t2,
+        v: v2,
+    } = right;
+
+    let mut v = Integer::from(&c1 * &d2) * &t2;
+    v += Integer::from(&d1 * &v2);
+    v *= &p1;
+    v += Integer::from(&d2 * &q2) * v1;
+
+    let mut c = c1 * &d2;
+    c += Integer::from(&d1 * &c2);
+
+    let mut t = t1 * &q2;
+    t += Integer::from(&p1 * &t2);
+
+    p1 *= p2;
+    q1 *= q2;
+    d1 *= d2;
+
+    Sums {
+        p: p1,
+        q: q1,
+        d: d1,
+        c,
+        t,
+        v,
+    }
+}
+
+/// Multithreaded `split`: halves of ranges of at least `PARALLEL_CUTOFF`
+/// terms run concurrently while the thread budget allows. The result is
+/// bit-identical to `split(n, a, b)`.
+pub fn split_parallel(n: u64, a: u64, b: u64, threads: usize) -> Sums {
+    if threads <= 1 || b.saturating_sub(a) < PARALLEL_CUTOFF {
+        return split(n, a, b);
+    }
+
+    let m = (a + b) / 2;
+    let left_threads = threads / 2;
+    let right_threads = threads - left_threads;
+
+    let (left, right) = thread::scope(|s| {
+        let left = s.spawn(|| split_parallel(n, a, m, left_threads));
+        let right = split_parallel(n, m, b, right_threads);
+        (
+            left.join().expect("binary splitting thread panicked"),
+            right,
+        )
+    });
+    merge(left, right)
+}
+
+/// γ to `precision` bits, good for `digits` decimal digits, and the number
+/// of terms summed (including those of ln n).
+pub fn gamma(digits: u64, precision: u64, threads: usize) -> (Float, u64) {
+    let n = n_for_digits(digits);
+    let terms = terms_for_n(n);
+    let sums = split_parallel(n, 1, terms + 1, threads);
+
+    // A/B = V / (D·(Q + T))
+    let a = Float::with_val_64(precision, &sums.v);
+    let b = Float::with_val_64(precision, sums.d * (sums.q + sums.t));
+    let (ln_n, ln_terms) = log::ln_ratio(n, 1, digits, precision, threads);
+    (a / b - ln_n, terms + ln_terms)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ComputeOptions, Constant, compute_constant};
+
+    #[test]
+    fn gamma_digits() {
+        let gamma = compute_constant(Constant::Gamma, 50, &ComputeOptions::default()).unwrap();
+        assert_eq!(
+            gamma.to_digit_string(),
+            "0.57721566490153286060651209008240243104215933593992"
+        );
+    }
+
+    #[test]
+    fn parallel_split_is_bit_identical() {
+        let n = 50;
+        let (a, b) = (1, 3 * PARALLEL_CUTOFF + 5);
+        let serial = split(n, a, b);
+        for threads in [2, 3, 4, 8] {
+            assert_eq!(split_parallel(n, a, b, threads), serial);
+        }
+    }
+
+    #[test]
+    fn empty_range_is_merge_identity() {
+        let range = split(50, 3, 20);
+        assert_eq!(merge(split(50, 3, 3), range.clone()), range);
+        assert_eq!(merge(range.clone(), split(50, 20, 20)), range);
+    }
+
+    #[test]
+    #[should_panic(expected = "reversed")]
+    fn reversed_range_panics() {
+        split(50, 5, 3);
+    }
+}
@@ -0,0 +1,236 @@
+//! Binary splitting for rational hypergeometric series.
+//!
+//! A series Σ_k a(k) · Π_{j=0..k} p(j)/q(j) with integer-valued p, q and a
+//! is described by implementing `Hypergeometric`. Binary splitting computes
+//! P(a, b), Q(a, b), T(a, b) with
+//!
+//!   P(a, b) = Π_{a<=j<b} p(j),   Q(a, b) = Π_{a<=j<b} q(j)
+//!   T(a, b) / Q(a, b) = Σ_{a<=k<b} a(k) · Π_{a<=j<=k} p(j)/q(j)
+//!
+//! so the sum of the first N terms is T(0, N) / Q(0, N). The Chudnovsky
+//! series (`Series`), arctan(1/x) (`machin::Arctan`) and e = Σ 1/k! are all
+//! instances; only the per-term polynomials differ, the merges are shared.
+//!
+//! Example, e = Σ 1/k! with p(k) = 1, q(k) = k (q(0) = 1), a(k) = 1:
+//!
+//! ```
+//! use pi_calculator::hypergeometric::{Hypergeometric, sum};
+//! use rug::Integer;
+//!
+//! struct E;
+//! impl Hypergeometric for E {
+//!     fn p(&self, _k: u64) -> Integer {
+//!         Integer::from(1)
+//!     }
+//!     fn q(&self, k: u64) -> Integer {
+//!         Integer::from(k.max(1))
+//!     }
+//!     fn a(&self, _k: u64) -> Integer {
+//!         Integer::from(1)
+//!     }
+//! }
+//!
+//! let e = sum(&E, 30, 128, 1);
+//! assert!((e - 2.718281828459045f64).abs() < 1e-15);
+//! ```
+
+use rug::{Float, Integer};
+use std::thread;
+
+/// Term ratios of a rational hypergeometric series; see the module docs.
+///
+/// `Sync` so that ranges can be split across threads.
+pub trait Hypergeometric: Sync {
+    /// Numerator of the ratio between term k and term k - 1 (any value
+    /// that makes term 0 come out right for k = 0, usually 1)
+    fn p(&self, k: u64) -> Integer;
+    /// Denominator of the same ratio
+    fn q(&self, k: u64) -> Integer;
+    /// Extra non-multiplicative factor of term k
+    fn a(&self, k: u64) -> Integer;
+
+    /// Approximate size in bits of q(k), used to estimate the cost of a
+    /// range for progress reporting.
+    fn term_bits(&self, k: u64) -> f64 {
+        self.q(k.max(1)).significant_bits() as f64
+    }
+
+    /// Estimated size in bits of Q(0, terms) (T(0, terms) is about the
+    /// same), for checking up front that a run fits in GMP's integers.
+    fn series_bits(&self, terms: u64) -> u64 {
+        (terms as f64 * self.term_bits(terms)) as u64
+    }
+}
+
+/// P, Q, T for the single term k.
+pub fn term<S: Hypergeometric + ?Sized>(series: &S, k: u64) -> (Integer, Integer, Integer) {
+    let p = series.p(k);
+    let q = series.q(k);
+    let t = series.a(k) * &p;
+    (p, q, t)
+}
+
+/// Binary splitting of `series` over terms [a, b). An empty range gives
+/// the identity of `merge`, (1, 1, 0).
+///
+/// Panics if `b < a`.
+pub fn split<S: Hypergeometric + ?Sized>(
+    series: &S,
+    a: u64,
+    b: u64,
+) -> (Integer, Integer, Integer) {
+    assert!(a <= b, "binary splitting range [{}, {}) is reversed", a, b);
+    if a == b {
+        (Integer::from(1), Integer::from(1), Integer::from(0))
+    } else if b - a == 1 {
+        // Base case: single term with index a
+        term(series, a)
+    } else {
+        // Recursive split
+        let m = (a + b) / 2;
+
+        // Left half
+        let left = split(series, a, m);
+        // Right half
+        let right = split(series, m, b);
+
+        merge(left, right)
+    }
+}
+
+/// Merge two adjacent subranges [a, m) and [m, b):
+///   P(a, b) = P(a, m) * P(m, b)
+///   Q(a, b) = Q(a, m) * Q(m, b)
+///   T(a, b) = Q(m, b) * T(a, m) + P(a, m) * T(m, b)
+pub fn merge(
+    left: (Integer, Integer, Integer),
+    right: (Integer, Integer, Integer),
+) -> (Integer, Integer, Integer) {
+    let (mut p1, mut q1, t1) = left;
+    let (p2, q2, t2) = right;
+
+    // Compute T into a fresh Integer
+    let mut t = Integer::from(&q2 * &t1);
+    t += Integer::from(&p1 * &t2);
+
+    // Reuse p1 and q1 as the result to reduce allocations
+    p1 *= p2; // p1 = p1 * p2
+    q1 *= q2; // q1 = q1 * q2
+
+    (p1, q1, t)
+}
+
+/// Subranges shorter than this many terms are always split serially:
+/// below it, thread start-up costs more than the multiplications save.
+pub const PARALLEL_CUTOFF: u64 = 1 << 10;
+
+/// Multithreaded binary splitting.
+///
+/// While `threads > 1` and the range spans at least `PARALLEL_CUTOFF`
+/// terms, the left and right halves run concurrently with the thread budget
+/// split between them, and the merge products of that level are also
+/// computed in parallel. Integer arithmetic is exact, so the result is
+/// bit-identical to `split(series, a, b)`.
+pub fn split_parallel<S: Hypergeometric + ?Sized>(
+    series: &S,
+    a: u64,
+    b: u64,
+    threads: usize,
+) -> (Integer, Integer, Integer) {
+    if threads <= 1 || b.saturating_sub(a) < PARALLEL_CUTOFF {
+        return split(series, a, b);
+    }
+
+    let m = (a + b) / 2;
+    let left_threads = threads / 2;
+    let right_threads = threads - left_threads;
+
+    let (left, right) = thread::scope(|s| {
+        let left = s.spawn(|| split_parallel(series, a, m, left_threads));
+        let right = split_parallel(series, m, b, right_threads);
+        (
+            left.join().expect("binary splitting thread panicked"),
+            right,
+        )
+    });
+
+    merge_parallel(left, right, threads)
+}
+
+/// Same as `merge`, but runs the four products on separate threads
+/// (two when fewer than four threads are available).
+pub fn merge_parallel(
+    left: (Integer, Integer, Integer),
+    right: (Integer, Integer, Integer),
+    threads: usize,
+) -> (Integer, Integer, Integer) {
+    let (p1, q1, t1) = left;
+    let (p2, q2, t2) = right;
+
+    thread::scope(|s| {
+        if threads >= 4 {
+            let qt = s.spawn(|| Integer::from(&q2 * &t1));
+            let pt = s.spawn(|| Integer::from(&p1 * &t2));
+            let pp = s.spawn(|| Integer::from(&p1 * &p2));
+            let q = Integer::from(&q1 * &q2);
+
+            let mut t = qt.join().expect("merge thread panicked");
+            t += pt.join().expect("merge thread panicked");
+            let p = pp.join().expect("merge thread panicked");
+            (p, q, t)
+        } else {
+            let pq = s.spawn(|| (Integer::from(&p1 * &p2), Integer::from(&q1 * &q2)));
+            let mut t = Integer::from(&q2 * &t1);
+            t += Integer::from(&p1 * &t2);
+
+            let (p, q) = pq.join().expect("merge thread panicked");
+            (p, q, t)
+        }
+    })
+}
+
+/// The sum of the first `terms` terms of `series` to `precision` bits
+/// (0 for no terms).
+pub fn sum<S: Hypergeometric + ?Sized>(
+    series: &S,
+    terms: u64,
+    precision: u64,
+    threads: usize,
+) -> Float {
+    let (_p, q, t) = split_parallel(series, 0, terms, threads);
+    Float::with_val_64(precision, &t) / Float::with_val_64(precision, &q)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::ESeries as E;
+
+    #[test]
+    fn parallel_split_is_bit_identical() {
+        let series = crate::Series::CHUDNOVSKY;
+        let (a, b) = (7, 3 * PARALLEL_CUTOFF + 5);
+        let serial = split(&series, a, b);
+        for threads in [2, 3, 4, 7, 8] {
+            assert_eq!(split_parallel(&series, a, b, threads), serial);
+        }
+
+        let e = split(&E, 0, 2 * PARALLEL_CUTOFF);
+        assert_eq!(split_parallel(&E, 0, 2 * PARALLEL_CUTOFF, 4), e);
+    }
+
+    #[test]
+    fn empty_range_is_merge_identity() {
+        let one = (Integer::from(1), Integer::from(1), Integer::from(0));
+        assert_eq!(split(&E, 5, 5), one);
+        assert_eq!(split_parallel(&E, 5, 5, 4), one);
+        assert_eq!(merge(split(&E, 0, 5), split(&E, 5, 5)), split(&E, 0, 5));
+        assert_eq!(sum(&E, 0, 64, 1), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "reversed")]
+    fn reversed_range_panics() {
+        split(&E, 5, 4);
+    }
+}
@@ -0,0 +1,533 @@
+//! Arbitrary-precision π using the Chudnovsky algorithm.
+//!
+//! The series is evaluated with binary splitting over `rug::Integer` and the
+//! final division is done once on `rug::Float`. The Gauss–Legendre iteration
+//! (`Algorithm::Agm`) and Machin-like arctan formulas (`Algorithm::Machin`)
+//! are available as alternatives:
+//!
+//! ```no_run
+//! let result = pi_calculator::compute_pi(1_000).unwrap();
+//! println!("{}", result.to_digit_string());
+//! ```
+//!
+//! Other constants (`Constant`) go through `compute_constant` and the same
+//! truncation and output path.
+
+use hypergeometric::{merge, merge_parallel};
+use rug::integer::IntegerExt64;
+use rug::{Complete, Float, Integer};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+pub mod agm;
+pub mod algebraic;
+pub mod bbp;
+pub mod cf;
+pub mod checkpoint;
+pub mod chudnovsky;
+pub mod constants;
+pub mod digits;
+pub mod gamma;
+pub mod hypergeometric;
+pub mod log;
+pub mod machin;
+pub mod output;
+pub mod progress;
+pub mod report;
+pub mod series;
+pub mod spigot;
+pub mod split;
+pub mod verify;
+
+pub use checkpoint::{Checkpoint, SeriesState};
+pub use chudnovsky::{binary_split, binary_split_parallel};
+pub use constants::Constant;
+pub use digits::parse_digit_spec;
+pub use hypergeometric::Hypergeometric;
+pub use machin::Formula;
+pub use progress::{Phase, PhaseTimings, Progress, ProgressMode};
+pub use report::RunReport;
+pub use series::Series;
+pub use split::{Tracking, binary_split_tracked};
+pub use verify::{CrossCheck, Verification, cross_check, verify_bbp};
+
+/// Method used to compute π.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Algorithm {
+    /// Chudnovsky series with binary splitting
+    #[default]
+    Chudnovsky,
+    /// Gauss–Legendre (Brent–Salamin) AGM iteration
+    Agm,
+    /// Machin-like arctan formula (`ComputeOptions::formula`)
+    Machin,
+}
+
+impl Algorithm {
+    /// Name as accepted by `--algorithm`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Algorithm::Chudnovsky => "chudnovsky",
+            Algorithm::Agm => "agm",
+            Algorithm::Machin => "machin",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Algorithm> {
+        match name.to_ascii_lowercase().as_str() {
+            "chudnovsky" => Some(Algorithm::Chudnovsky),
+            "agm" | "gauss-legendre" => Some(Algorithm::Agm),
+            "machin" => Some(Algorithm::Machin),
+            _ => None,
+        }
+    }
+
+    /// Human-readable name for messages.
+    pub fn description(self) -> &'static str {
+        match self {
+            Algorithm::Chudnovsky => "Chudnovsky",
+            Algorithm::Agm => "Gauss–Legendre AGM",
+            Algorithm::Machin => "Machin-like arctan",
+        }
+    }
+}
+
+/// Result of a π computation (or of another `Constant`).
+///
+/// `integer` holds π * base^digits truncated toward zero, i.e. the integer
+/// part followed by the requested fractional digits with no radix point.
+#[derive(Debug, Clone)]
+pub struct PiResult {
+    /// Constant computed; "π" below stands for it
+    pub constant: Constant,
+    /// Number of fractional digits
+    pub digits: u64,
+    /// Radix of the digits, 2..=36
+    pub base: u32,
+    /// How π was computed (only meaningful for `Constant::Pi`)
+    pub algorithm: Algorithm,
+    /// Series summed, for `Algorithm::Chudnovsky`
+    pub series: Option<Series>,
+    /// trunc(π * base^digits)
+    pub integer: Integer,
+    /// Number of series terms summed (all arctans for Machin-like
+    /// formulas), or AGM iterations
+    pub terms: u64,
+    /// Float precision (bits) used for the final division
+    pub precision: u64,
+    /// Wall time spent computing `integer`
+    pub elapsed: Duration,
+    /// Breakdown of `elapsed` by phase
+    pub timings: PhaseTimings,
+}
+
+impl PiResult {
+    /// Integer part followed by exactly `digits` fractional digits, without
+    /// the radix point ("3" + decimals in base 10, "11" + bits in base 2).
+    ///
+    /// This is the single radix conversion of `integer`; the writers in
+    /// `output` slice it rather than copying it.
+    pub fn expansion_string(&self) -> String {
+        expansion_of(&self.integer, self.digits, self.base)
+    }
+
+    /// Expansion formatted as "<integer part>.<digits>", e.g. "3.14159".
+    pub fn to_digit_string(&self) -> String {
+        // Insert the radix point before the fractional digits
+        let mut pi_str = self.expansion_string();
+        pi_str.insert(pi_str.len() - self.digits as usize, '.');
+        pi_str
+    }
+}
+
+/// Digits of `integer` in `base`, zero-padded to at least `digits + 1`
+/// characters (integer part + fraction), with a leading '-' if negative.
+pub(crate) fn expansion_of(integer: &Integer, digits: u64, base: u32) -> String {
+    if *integer < 0 {
+        return format!("-{}", expansion_of(&integer.as_abs(), digits, base));
+    }
+    let pi_str = integer.to_string_radix(base as i32);
+    // `check_digits` guarantees the digit count fits in memory
+    let digits = digits as usize;
+
+    // Pad by hand: format! widths are limited to u16
+    if pi_str.len() <= digits {
+        let mut padded = "0".repeat(digits + 1 - pi_str.len());
+        padded.push_str(&pi_str);
+        padded
+    } else {
+        pi_str
+    }
+}
+
+/// Compute required precision in bits:
+/// bits ≈ digits * log2(10) + safety_margin
+pub fn precision_for_digits(digits: u64) -> u64 {
+    let bits_per_digit = std::f64::consts::LOG2_10;
+    let extra_bits = 256.0; // safety margin
+    (digits as f64 * bits_per_digit + extra_bits) as u64
+}
+
+/// Check that `digits` can be computed on this machine at all.
+///
+/// The limits are, in order: MPFR's maximum precision, GMP's integer size
+/// (limb counts are a C `int`), and the address space, which must hold the
+/// scaled result plus its digit string.
+pub fn check_digits(digits: u64) -> Result<(), String> {
+    check_digits_for(&Series::CHUDNOVSKY, digits)
+}
+
+/// `check_digits` with the integers sized for `series` instead of the
+/// Chudnovsky series.
+pub fn check_digits_for(series: &Series, digits: u64) -> Result<(), String> {
+    check_size(digits, series.series_bits(series.terms_for_digits(digits)))
+}
+
+/// `check_digits` for a run whose largest integers have about
+/// `integer_bits` bits (0 when they stay near the result's precision).
+pub(crate) fn check_size(digits: u64, integer_bits: u64) -> Result<(), String> {
+    let precision = precision_for_digits(digits);
+    if precision > rug::float::prec_max_64() {
+        return Err(format!(
+            "Too many digits ({}): needs {} bits of precision, MPFR supports at most {}",
+            digits,
+            precision,
+            rug::float::prec_max_64()
+        ));
+    }
+
+    // Q(0, N) and T(0, N) outgrow the final result, so size against them.
+    // GMP limbs are pointer-sized on every supported target.
+    let bits = integer_bits.max(precision);
+    let limbs = bits / u64::from(usize::BITS) + 1;
+    if limbs > i32::MAX as u64 {
+        return Err(format!(
+            "Too many digits ({}): a {}-bit integer exceeds GMP's size limit",
+            digits, bits
+        ));
+    }
+
+    let bytes = (bits / 8).checked_add(digits);
+    if bytes.is_none_or(|b| b > isize::MAX as u64) {
+        return Err(format!(
+            "Too many digits ({}): result does not fit in the address space",
+            digits
+        ));
+    }
+
+    Ok(())
+}
+
+/// Widen MPFR's exponent range on this thread to the largest it supports.
+///
+/// The default maximum exponent is 2^30 - 1, so any Float above about a
+/// billion bits (Q and T of a long series, or the result scaled by
+/// base^digits) would overflow to infinity.
+pub(crate) fn widen_exponent_range() {
+    // SAFETY: plain setters; widening leaves every existing Float in range
+    unsafe {
+        gmp_mpfr_sys::mpfr::set_emax(gmp_mpfr_sys::mpfr::get_emax_max());
+        gmp_mpfr_sys::mpfr::set_emin(gmp_mpfr_sys::mpfr::get_emin_min());
+    }
+}
+
+/// Decimal digits carrying as much information as `digits` digits in `base`.
+pub fn decimal_equivalent(digits: u64, base: u32) -> u64 {
+    if base == 10 {
+        digits
+    } else {
+        (digits as f64 * f64::from(base).log10()).ceil() as u64
+    }
+}
+
+/// x * base^digits truncated toward zero, i.e. x truncated to `digits`
+/// digits in `base` (floor(x * base^digits) for positive x).
+///
+/// For power-of-two bases the scaling is an exact shift of the Float's
+/// exponent, so the digits come straight from its binary mantissa.
+pub fn truncate_to_digits(x: &Float, digits: u64, base: u32) -> Integer {
+    let scaled = if base.is_power_of_two() {
+        let shift = digits * u64::from(base.trailing_zeros());
+        Float::with_val_64(x.prec_64(), x << shift as usize)
+    } else {
+        // Scale by base^digits using Integer -> Float (explicit, safe)
+        let scale_int = Integer::u64_pow_u64(u64::from(base), digits).complete();
+        let scale = Float::with_val_64(x.prec_64(), &scale_int);
+        x * scale
+    };
+
+    // Truncate instead of round, then convert to Integer
+    scaled
+        .trunc()
+        .to_integer()
+        .expect("Failed to convert trunc(x * base^digits) to Integer")
+}
+
+/// Tuning knobs for `compute_pi_with` and `compute_constant`.
+#[derive(Debug, Clone)]
+pub struct ComputeOptions {
+    /// Worker threads for binary splitting; 1 runs the serial path
+    pub threads: usize,
+    /// Save finished subranges here and resume from them on restart
+    pub checkpoint_dir: Option<PathBuf>,
+    /// Start the series from a saved (P, Q, T) state instead of term 0
+    pub extend_from: Option<PathBuf>,
+    /// Save the final (P, Q, T) of the series here
+    pub save_state: Option<PathBuf>,
+    /// Report phases and series progress here
+    pub progress: Option<Arc<Progress>>,
+    /// Output radix, 2..=36; `digits` counts digits in this base
+    pub base: u32,
+    /// Method used to compute π
+    pub algorithm: Algorithm,
+    /// Series for `Algorithm::Chudnovsky` (the Chudnovsky series itself by
+    /// default)
+    pub series: Series,
+    /// Formula for `Algorithm::Machin`
+    pub formula: Formula,
+}
+
+impl Default for ComputeOptions {
+    fn default() -> Self {
+        ComputeOptions {
+            threads: 1,
+            checkpoint_dir: None,
+            extend_from: None,
+            save_state: None,
+            progress: None,
+            base: 10,
+            algorithm: Algorithm::Chudnovsky,
+            series: Series::CHUDNOVSKY,
+            formula: Formula::default(),
+        }
+    }
+}
+
+impl ComputeOptions {
+    /// The first option set that only a single binary-splitting run can
+    /// honour (checkpoints and saved states), as its command-line flag.
+    pub(crate) fn split_only_flag(&self) -> Option<&'static str> {
+        [
+            (self.checkpoint_dir.is_some(), "--checkpoint-dir"),
+            (self.extend_from.is_some(), "--extend-from"),
+            (self.save_state.is_some(), "--save-state"),
+        ]
+        .into_iter()
+        .find_map(|(set, flag)| set.then_some(flag))
+    }
+}
+
+/// Compute π truncated to `digits` decimals on a single thread.
+pub fn compute_pi(digits: u64) -> Result<PiResult, String> {
+    compute_pi_with(digits, &ComputeOptions::default())
+}
+
+/// Compute π truncated to `digits` digits (in `options.base`) with the
+/// given options.
+pub fn compute_pi_with(digits: u64, options: &ComputeOptions) -> Result<PiResult, String> {
+    compute_constant(Constant::Pi, digits, options)
+}
+
+/// Compute `constant` truncated to `digits` digits (in `options.base`).
+///
+/// `options.algorithm`, `series` and `formula` only apply to π.
+pub fn compute_constant(
+    constant: Constant,
+    digits: u64,
+    options: &ComputeOptions,
+) -> Result<PiResult, String> {
+    if !(2..=36).contains(&options.base) {
+        return Err(format!("Base must be in 2..=36, got {}", options.base));
+    }
+    // Series terms and precision are sized in decimal digits
+    let decimal_digits = decimal_equivalent(digits, options.base);
+    check_size(
+        decimal_digits,
+        constants::integer_bits(constant, decimal_digits, options),
+    )?;
+    widen_exponent_range();
+    let start = Instant::now();
+
+    let progress = options.progress.as_deref();
+    let mut timings = PhaseTimings::default();
+    let mut enter = |phase| {
+        timings.enter(phase);
+        if let Some(progress) = progress {
+            progress.phase(phase);
+        }
+    };
+
+    let precision = precision_for_digits(decimal_digits);
+    let (value, terms) = match (constant, options.algorithm) {
+        (Constant::Pi, Algorithm::Chudnovsky) => {
+            chudnovsky_pi(digits, decimal_digits, precision, options, &mut enter)?
+        }
+        (Constant::Pi, Algorithm::Agm) => {
+            if let Some(flag) = options.split_only_flag() {
+                return Err(format!("{} only applies to the Chudnovsky series", flag));
+            }
+            enter(Phase::Agm);
+            let pi = agm::pi_agm(precision);
+            enter(Phase::Division);
+            pi
+        }
+        (Constant::Pi, Algorithm::Machin) => {
+            if let Some(flag) = options.split_only_flag() {
+                return Err(format!("{} only applies to the Chudnovsky series", flag));
+            }
+            enter(Phase::Series);
+            let pi =
+                machin::pi_machin(&options.formula, decimal_digits, precision, options.threads);
+            enter(Phase::Division);
+            pi
+        }
+        (constant, _) => constants::evaluate(
+            constant,
+            digits,
+            decimal_digits,
+            precision,
+            options,
+            &mut enter,
+        )?,
+    };
+
+    let integer = truncate_to_digits(&value, digits, options.base);
+    timings.stop();
+
+    let is_pi = constant == Constant::Pi;
+    Ok(PiResult {
+        constant,
+        digits,
+        base: options.base,
+        algorithm: options.algorithm,
+        series: (is_pi && options.algorithm == Algorithm::Chudnovsky).then_some(options.series),
+        integer,
+        terms,
+        precision,
+        elapsed: start.elapsed(),
+        timings,
+    })
+}
+
+/// π to `precision` bits from `options.series`, and the number of terms
+/// summed. Returns in the division phase, which the caller's truncation
+/// belongs to.
+pub(crate) fn chudnovsky_pi(
+    digits: u64,
+    decimal_digits: u64,
+    precision: u64,
+    options: &ComputeOptions,
+    enter: &mut impl FnMut(Phase),
+) -> Result<(Float, u64), String> {
+    let series = &options.series;
+
+    enter(Phase::Series);
+    let terms = series.terms_for_digits(decimal_digits);
+    let (_p, q, t) = split_tracked(series, series.name, digits, terms, options)?;
+
+    // π = (Q * factor * sqrt(radicand)) / T, e.g. factor = 426880 and
+    // radicand = 10005 for Chudnovsky
+    enter(Phase::Sqrt);
+    let sqrt = Float::with_val_64(precision, series.radicand).sqrt();
+
+    enter(Phase::Division);
+    // Numerator and denominator as Integers first, then convert once to Float
+    let (factor_num, factor_den) = series.factor;
+    let q_times_c = Integer::from(factor_num) * &q;
+    let numerator = Float::with_val_64(precision, q_times_c) * sqrt;
+    let denominator = if factor_den == 1 {
+        Float::with_val_64(precision, &t)
+    } else {
+        Float::with_val_64(precision, Integer::from(factor_den) * &t)
+    };
+    Ok((numerator / denominator, terms))
+}
+
+/// Binary splitting of `series` over [0, terms) with the checkpointing,
+/// progress and threads in `options`. `name` and `digits` identify the run
+/// in the checkpoint directory and `name` the series in saved states.
+///
+/// With `options.extend_from`, [0, N) comes from the saved state and only
+/// [N, terms) is split, then merged onto it. With `options.save_state`, the
+/// final triple is saved for a later extension.
+pub(crate) fn split_tracked(
+    series: &dyn Hypergeometric,
+    name: &str,
+    digits: u64,
+    terms: u64,
+    options: &ComputeOptions,
+) -> Result<(Integer, Integer, Integer), String> {
+    let base = match &options.extend_from {
+        Some(path) => {
+            let state = SeriesState::load(path)?;
+            if state.series != name {
+                return Err(format!(
+                    "State {} is for the {} series, this run sums {}",
+                    path.display(),
+                    state.series,
+                    name
+                ));
+            }
+            if state.terms > terms {
+                return Err(format!(
+                    "State {} already covers {} terms, more than the {} this run needs",
+                    path.display(),
+                    state.terms,
+                    terms
+                ));
+            }
+            Some(state)
+        }
+        None => None,
+    };
+    let start = base.as_ref().map_or(0, |state| state.terms);
+
+    let progress = options.progress.as_deref();
+    let ckpt = match &options.checkpoint_dir {
+        Some(dir) => Some(Checkpoint::open(dir, name, digits, terms)?),
+        None => None,
+    };
+    let tracking = Tracking::new(series, terms - start, ckpt.as_ref(), progress);
+    if let Some(progress) = progress {
+        progress.series_total(terms - start, tracking.work(start, terms));
+    }
+
+    let pqt = match base {
+        Some(state) if start == terms => state.pqt,
+        Some(state) => {
+            let rest = binary_split_tracked(start, terms, options.threads, &tracking)?;
+            // Same merge rule as inside the split, at the old end
+            if options.threads > 1 {
+                merge_parallel(state.pqt, rest, options.threads)
+            } else {
+                merge(state.pqt, rest)
+            }
+        }
+        None => binary_split_tracked(0, terms, options.threads, &tracking)?,
+    };
+
+    if let Some(path) = &options.save_state {
+        let state = SeriesState {
+            series: name.to_string(),
+            terms,
+            pqt,
+        };
+        state.save(path)?;
+        return Ok(state.pqt);
+    }
+    Ok(pqt)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn runs_widen_the_exponent_range() {
+        compute_pi(10).unwrap();
+        // 2^(2^32) is far past MPFR's default maximum exponent of 2^30 - 1
+        let huge = Float::with_val(64, 1u32) << (1usize << 32);
+        assert!(huge.is_finite());
+    }
+}
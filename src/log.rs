@@ -0,0 +1,198 @@
+//! Natural logarithms from Machin-like atanh formulas.
+//!
+//!   atanh(u/v) = Σ_k (u/v)^(2k+1) / (2k+1)
+//!
+//! is hypergeometric with p(0) = u, q(0) = v, p(k) = (2k-1) u²,
+//! q(k) = (2k+1) v², and is summed by binary splitting like arctan in
+//! `machin`. ln 2 and ln 10 use fixed atanh(1/x) combinations; ln(p/q) is
+//! reduced to m·ln 2 + 2·atanh((r-1)/(r+1)) with r = p / (q·2^m) within a
+//! factor √2 of 1, so the atanh argument is at most 0.1716.
+
+use crate::hypergeometric::{self, Hypergeometric};
+use rug::{Float, Integer};
+
+/// ln 2 = 18 atanh(1/26) - 2 atanh(1/4801) + 8 atanh(1/8749)
+pub const LN2: &[(i64, u64)] = &[(18, 26), (-2, 4801), (8, 8749)];
+
+/// ln 10 = 46 atanh(1/31) + 34 atanh(1/49) + 20 atanh(1/161)
+pub const LN10: &[(i64, u64)] = &[(46, 31), (34, 49), (20, 161)];
+
+/// atanh(u/v) as a hypergeometric series, 0 <= u < v.
+#[derive(Debug, Clone)]
+pub struct Atanh {
+    u: Integer,
+    v: Integer,
+    u2: Integer,
+    v2: Integer,
+}
+
+impl Atanh {
+    pub fn new(u: Integer, v: Integer) -> Atanh {
+        let u2 = Integer::from(u.square_ref());
+        let v2 = Integer::from(v.square_ref());
+        Atanh { u, v, u2, v2 }
+    }
+
+    /// atanh(1/x)
+    pub fn recip(x: u64) -> Atanh {
+        Atanh::new(Integer::from(1), Integer::from(x))
+    }
+
+    /// Number of terms for `digits` decimal digits: each term adds
+    /// 2·log10(v/u) digits.
+    pub fn terms_for_digits(&self, digits: u64) -> u64 {
+        if self.u == 0 {
+            return 1;
+        }
+        let ratio = self.v.to_f64().log10() - self.u.to_f64().log10();
+        (digits as f64 / (2.0 * ratio)) as u64 + 2
+    }
+}
+
+impl Hypergeometric for Atanh {
+    fn p(&self, k: u64) -> Integer {
+        if k == 0 {
+            self.u.clone()
+        } else {
+            Integer::from(&self.u2 * (2 * k - 1))
+        }
+    }
+
+    fn q(&self, k: u64) -> Integer {
+        if k == 0 {
+            self.v.clone()
+        } else {
+            Integer::from(&self.v2 * (2 * k + 1))
+        }
+    }
+
+    fn a(&self, _k: u64) -> Integer {
+        Integer::from(1)
+    }
+
+    fn term_bits(&self, k: u64) -> f64 {
+        self.v2.significant_bits() as f64 + ((2 * k + 1) as f64).log2()
+    }
+}
+
+/// Σ c·atanh(1/x) over `formula` to `precision` bits, good for `digits`
+/// decimal digits, and the number of terms summed.
+pub fn atanh_sum(
+    formula: &[(i64, u64)],
+    digits: u64,
+    precision: u64,
+    threads: usize,
+) -> (Float, u64) {
+    let mut sum = Float::with_val_64(precision, 0);
+    let mut total_terms = 0;
+    for &(c, x) in formula {
+        let series = Atanh::recip(x);
+        let terms = series.terms_for_digits(digits);
+        sum += hypergeometric::sum(&series, terms, precision, threads) * c;
+        total_terms += terms;
+    }
+    (sum, total_terms)
+}
+
+/// Estimated size in bits of the integers summing `formula` to `digits`
+/// decimal digits, for the up-front size check.
+pub fn series_bits(formula: &[(i64, u64)], digits: u64) -> u64 {
+    formula
+        .iter()
+        .map(|&(_, x)| {
+            let series = Atanh::recip(x);
+            series.series_bits(series.terms_for_digits(digits))
+        })
+        .sum()
+}
+
+/// `series_bits` for `ln_ratio(p, q, digits, ..)`.
+pub fn ln_ratio_bits(p: u64, q: u64, digits: u64) -> u64 {
+    let (m, series, _) = reduce(p, q);
+    let bits = series.series_bits(series.terms_for_digits(digits));
+    if m != 0 {
+        bits + series_bits(LN2, digits)
+    } else {
+        bits
+    }
+}
+
+/// ln(p/q) to `precision` bits, good for `digits` decimal digits, and the
+/// number of terms summed. Negative for p < q.
+pub fn ln_ratio(p: u64, q: u64, digits: u64, precision: u64, threads: usize) -> (Float, u64) {
+    let (m, series, negative) = reduce(p, q);
+    let mut terms = series.terms_for_digits(digits);
+    let mut ln = hypergeometric::sum(&series, terms, precision, threads) * 2u32;
+    if negative {
+        ln = -ln;
+    }
+
+    if m != 0 {
+        let (ln2, ln2_terms) = atanh_sum(LN2, digits, precision, threads);
+        ln += ln2 * m;
+        terms += ln2_terms;
+    }
+    (ln, terms)
+}
+
+/// Split ln(p/q) into m·ln 2 ± 2·atanh(u/v): returns m, the atanh series
+/// and whether its sign is negative.
+fn reduce(p: u64, q: u64) -> (i64, Atanh, bool) {
+    // m = round(log2(p/q)), so that r = p / (q·2^m) is in [1/√2, √2)
+    let mut m = i64::from(64 - p.leading_zeros()) - i64::from(64 - q.leading_zeros());
+    let scaled = |m: i64| -> (Integer, Integer) {
+        if m >= 0 {
+            (Integer::from(p), Integer::from(q) << m as u32)
+        } else {
+            (Integer::from(p) << (-m) as u32, Integer::from(q))
+        }
+    };
+    loop {
+        let (n, d) = scaled(m);
+        let (n2, d2) = (n.square(), d.square());
+        if n2 >= Integer::from(&d2 * 2u32) {
+            m += 1;
+        } else if Integer::from(&n2 * 2u32) < d2 {
+            m -= 1;
+        } else {
+            break;
+        }
+    }
+
+    // ln r = 2·atanh((n - d) / (n + d))
+    let (n, d) = scaled(m);
+    let u = Integer::from(&n - &d);
+    let v = n + d;
+    let negative = u < 0;
+    (m, Atanh::new(u.abs(), v), negative)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ComputeOptions, Constant, compute_constant};
+
+    #[test]
+    fn log_digits() {
+        let digits = |constant| {
+            compute_constant(constant, 50, &ComputeOptions::default())
+                .unwrap()
+                .to_digit_string()
+        };
+        assert_eq!(
+            digits(Constant::Ln2),
+            "0.69314718055994530941723212145817656807550013436025"
+        );
+        assert_eq!(
+            digits(Constant::Ln10),
+            "2.30258509299404568401799145468436420760110148862877"
+        );
+        assert_eq!(
+            digits(Constant::Ln(3, 2)),
+            "0.40546510810816438197801311546434913657199042346249"
+        );
+        assert_eq!(
+            digits(Constant::Ln(2, 3)),
+            "-0.40546510810816438197801311546434913657199042346249"
+        );
+    }
+}
@@ -0,0 +1,246 @@
+//! Machin-like arctan formulas:
+//!
+//!   π/4 = Σ cᵢ·arctan(1/xᵢ)
+//!
+//! Each arctan(1/x) = Σ_k (-1)^k / ((2k+1) x^(2k+1)) is summed by binary
+//! splitting with the same P/Q/T merge as the Chudnovsky series. Consecutive
+//! terms have the rational ratio -(2k-1) / ((2k+1) x²), so with
+//!   p(0) = 1, q(0) = x,  p(k) = -(2k-1), q(k) = (2k+1) x²
+//! the sum over [0, N) is T(0, N) / Q(0, N) (see `Arctan`).
+
+use crate::hypergeometric::{self, Hypergeometric};
+use rug::{Float, Integer};
+
+/// Built-in formulas as (name, [(c, x)]) with π/4 = Σ c·arctan(1/x).
+pub const FORMULAS: &[(&str, &[(i64, u64)])] = &[
+    ("machin", &[(4, 5), (-1, 239)]),
+    ("takano", &[(12, 49), (32, 57), (-5, 239), (12, 110443)]),
+    ("stormer", &[(44, 57), (7, 239), (-12, 682), (24, 12943)]),
+];
+
+/// Largest |c| accepted, which keeps the exact check's product below a few
+/// million bits per term.
+const MAX_COEFFICIENT: u64 = 100_000;
+
+/// A Machin-like formula: π/4 = Σ c·arctan(1/x) over `terms`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Formula {
+    pub name: String,
+    /// (coefficient, x) pairs
+    pub terms: Vec<(i64, u64)>,
+}
+
+impl Default for Formula {
+    fn default() -> Self {
+        Formula::by_name("machin").expect("built-in formula")
+    }
+}
+
+impl Formula {
+    /// One of the built-in `FORMULAS` (case-insensitive; "størmer" is
+    /// accepted for "stormer").
+    pub fn by_name(name: &str) -> Option<Formula> {
+        let name = name.to_lowercase().replace('ø', "o");
+        FORMULAS
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map(|(n, terms)| Formula {
+                name: n.to_string(),
+                terms: terms.to_vec(),
+            })
+    }
+
+    /// A built-in name, or a coefficient list "c:x,c:x,..." such as
+    /// "4:5,-1:239". Lists are checked to actually sum to π/4.
+    pub fn parse(spec: &str) -> Result<Formula, String> {
+        if let Some(formula) = Formula::by_name(spec) {
+            return Ok(formula);
+        }
+        if !spec.contains(':') {
+            let names: Vec<&str> = FORMULAS.iter().map(|(n, _)| *n).collect();
+            return Err(format!(
+                "Unknown formula \"{}\", expected {} or a list like 4:5,-1:239",
+                spec,
+                names.join(", ")
+            ));
+        }
+
+        let terms =
+            spec.split(',')
+                .map(|pair| {
+                    let (c, x) = pair.split_once(':').ok_or_else(|| {
+                        format!("Invalid formula term \"{}\", expected c:x", pair)
+                    })?;
+                    let c: i64 = c
+                        .trim()
+                        .parse()
+                        .map_err(|_| format!("Invalid coefficient \"{}\"", c))?;
+                    let x: u64 = x.trim().parse().ok().filter(|&x| x >= 2).ok_or_else(|| {
+                        format!("Invalid arctan argument 1/\"{}\", need x >= 2", x)
+                    })?;
+                    Ok((c, x))
+                })
+                .collect::<Result<Vec<_>, String>>()?;
+
+        let formula = Formula {
+            name: spec.to_string(),
+            terms,
+        };
+        formula.check()?;
+        Ok(formula)
+    }
+
+    /// Check exactly that the formula is an identity for π/4.
+    ///
+    /// arctan(1/x) is the argument of x + i, so Σ c·arctan(1/x) is π/4 modulo
+    /// 2π exactly when Π (x + i)^c, taking (x - i)^|c| for c < 0, is a
+    /// positive multiple of 1 + i. The f64 sum then rules out π/4 + 2kπ.
+    pub fn check(&self) -> Result<(), String> {
+        if let Some(&(c, _)) = self
+            .terms
+            .iter()
+            .find(|(c, _)| c.unsigned_abs() > MAX_COEFFICIENT)
+        {
+            return Err(format!(
+                "Coefficient {} is too large, at most {} in absolute value",
+                c, MAX_COEFFICIENT
+            ));
+        }
+
+        let mut product = (Integer::from(1), Integer::from(0));
+        for &(c, x) in &self.terms {
+            let base = (Integer::from(x), Integer::from(c.signum()));
+            product = gaussian_mul(&product, &gaussian_pow(base, c.unsigned_abs()));
+        }
+        let sum: f64 = self
+            .terms
+            .iter()
+            .map(|&(c, x)| c as f64 * (1.0 / x as f64).atan())
+            .sum();
+        let (re, im) = product;
+        if re <= 0 || re != im || (sum - std::f64::consts::FRAC_PI_4).abs() > 1.0 {
+            return Err(format!(
+                "Formula \"{}\" is not an identity for π/4 (it sums to about {})",
+                self.name, sum
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// (a + bi)·(c + di) over the Gaussian integers.
+fn gaussian_mul((a, b): &(Integer, Integer), (c, d): &(Integer, Integer)) -> (Integer, Integer) {
+    (
+        Integer::from(a * c) - Integer::from(b * d),
+        Integer::from(a * d) + Integer::from(b * c),
+    )
+}
+
+/// (a + bi)^e by repeated squaring.
+fn gaussian_pow(mut base: (Integer, Integer), mut e: u64) -> (Integer, Integer) {
+    let mut result = (Integer::from(1), Integer::from(0));
+    while e > 0 {
+        if e & 1 == 1 {
+            result = gaussian_mul(&result, &base);
+        }
+        e >>= 1;
+        if e > 0 {
+            base = gaussian_mul(&base, &base);
+        }
+    }
+    result
+}
+
+/// Number of arctan(1/x) terms for `digits` decimal digits: each term adds
+/// 2·log10(x) digits.
+pub fn terms_for_digits(x: u64, digits: u64) -> u64 {
+    (digits as f64 / (2.0 * (x as f64).log10())) as u64 + 2
+}
+
+/// Estimated size in bits of the integers summing `formula` to `digits`
+/// decimal digits, for the up-front size check.
+pub fn series_bits(formula: &Formula, digits: u64) -> u64 {
+    formula
+        .terms
+        .iter()
+        .map(|&(_, x)| Arctan { x }.series_bits(terms_for_digits(x, digits)))
+        .sum()
+}
+
+/// arctan(1/x) as a hypergeometric series.
+#[derive(Debug, Clone, Copy)]
+pub struct Arctan {
+    pub x: u64,
+}
+
+impl Hypergeometric for Arctan {
+    /// p(k) = -(2k - 1), and 1 for k = 0
+    fn p(&self, k: u64) -> Integer {
+        if k == 0 {
+            Integer::from(1)
+        } else {
+            -Integer::from(2 * k - 1)
+        }
+    }
+
+    /// q(k) = (2k + 1) x², and x for k = 0
+    fn q(&self, k: u64) -> Integer {
+        if k == 0 {
+            Integer::from(self.x)
+        } else {
+            Integer::from(self.x) * self.x * (2 * k + 1)
+        }
+    }
+
+    fn a(&self, _k: u64) -> Integer {
+        Integer::from(1)
+    }
+}
+
+/// π to `precision` bits from `formula`, good for `digits` decimal digits,
+/// and the total number of arctan terms summed.
+pub fn pi_machin(formula: &Formula, digits: u64, precision: u64, threads: usize) -> (Float, u64) {
+    let mut sum = Float::with_val_64(precision, 0);
+    let mut total_terms = 0;
+
+    for &(c, x) in &formula.terms {
+        let terms = terms_for_digits(x, digits);
+        sum += hypergeometric::sum(&Arctan { x }, terms, precision, threads) * c;
+        total_terms += terms;
+    }
+
+    (sum * 4u32, total_terms)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Algorithm, ComputeOptions, compute_pi, compute_pi_with};
+
+    #[test]
+    fn formulas_match_chudnovsky() {
+        let chudnovsky = compute_pi(2000).unwrap();
+        for (name, _) in FORMULAS {
+            let options = ComputeOptions {
+                algorithm: Algorithm::Machin,
+                formula: Formula::by_name(name).unwrap(),
+                ..ComputeOptions::default()
+            };
+            let machin = compute_pi_with(2000, &options).unwrap();
+            assert_eq!(machin.integer, chudnovsky.integer, "{}", name);
+        }
+    }
+
+    #[test]
+    fn check_is_exact() {
+        for (name, _) in FORMULAS {
+            Formula::by_name(name).unwrap().check().unwrap();
+        }
+        assert!(Formula::parse("4:5,-1:239").is_ok());
+        // Off by about 1e-14, within f64 rounding of π/4
+        assert!(Formula::parse("4:5,-1:239,1:100000000000000").is_err());
+        // 9·π/4 = π/4 + 2π
+        assert!(Formula::parse("36:5,-9:239").is_err());
+        assert!(Formula::parse("400000:5,-100000:239").is_err());
+    }
+}
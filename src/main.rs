@@ -1,177 +1,71 @@
-use rug::{Float, Integer};
-use rug::ops::Pow;
+//! Accepts arbitrary digit counts: 1, 123, 7615236, etc.
+//!
+//! Accepts suffixes (case-insensitive):
+//!   K = 1_000
+//!   M = 1_000_000
+//!   G = 1_000_000_000
+//!   T = 1_000_000_000_000
+//!
+//! Also accepts scientific notation: 1e6, 3E7, etc.
+//!
+//! Works with:
+//!   cargo run --release                  (defaults to 100_000 digits)
+//!   cargo run --release -- 12345
+//!   cargo run --release -- --calculate 1K
+//!   cargo run --release -- --digits 10M
+//!   cargo run --release -- --digits 10M --threads 8
+//!
+//! All of the computation lives in the `pi_calculator` library; this binary
+//! only parses arguments and prints the result.
+
+use pi_calculator::{
+    Algorithm, ComputeOptions, Constant, Formula, Phase, Progress, ProgressMode, RunReport, Series,
+    bbp, cf, compute_constant, cross_check, output, parse_digit_spec, spigot, verify_bbp,
+};
+use rug::float::Round;
+use std::io::{self, BufWriter};
+use std::path::PathBuf;
+use std::sync::Arc;
 use std::time::Instant;
 
-/// Accepts arbitrary digit counts: 1, 123, 7615236, etc.
-///
-/// Accepts suffixes (case-insensitive):
-///   K = 1_000
-///   M = 1_000_000
-///   G = 1_000_000_000
-///   T = 1_000_000_000_000
-///
-/// Also accepts scientific notation: 1e6, 3E7, etc.
-///
-/// Works with:
-///   cargo run --release                  (defaults to 100_000 digits)
-///   cargo run --release -- 12345
-///   cargo run --release -- --calculate 1K
-///   cargo run --release -- --digits 10M
-
-/// Binary splitting for the Chudnovsky series
-///
-/// We compute P(a, b), Q(a, b), T(a, b) such that:
-///   π = (Q(0, N) * 426880 * sqrt(10005)) / T(0, N)
-fn binary_split(a: u64, b: u64) -> (Integer, Integer, Integer) {
-    if b - a == 1 {
-        // Base case: single term with index a
-        if a == 0 {
-            let p = Integer::from(1);
-            let q = Integer::from(1);
-            let t = Integer::from(13591409);
-            (p, q, t)
-        } else {
-            let k = a;
-
-            // P_k = (6k - 5)(2k - 1)(6k - 1)
-            let term1 = Integer::from(6 * k - 5);
-            let term2 = Integer::from(2 * k - 1);
-            let term3 = Integer::from(6 * k - 1);
-            let p = term1 * term2 * term3;
-
-            // Q_k = k^3 * C^3 / 24, where C = 640320
-            // C^3 / 24 = 10939058860032000
-            let c3_over_24 = Integer::from(10939058860032000u64);
-            let q = Integer::from(k).pow(3u32) * c3_over_24;
-
-            // T_k = (-1)^k * (13591409 + 545140134 k) * P_k
-            let val = Integer::from(13591409u64 + 545140134u64 * k);
-            let t = if k % 2 == 1 {
-                // use -val (moves val) to avoid NegIncomplete
-                -val * &p
-            } else {
-                val * &p
-            };
-
-            (p, q, t)
-        }
-    } else {
-        // Recursive split
-        let m = (a + b) / 2;
-
-        // Left half
-        let (mut p1, mut q1, t1) = binary_split(a, m);
-        // Right half
-        let (p2, q2, t2) = binary_split(m, b);
-
-        // Merge:
-        // P(a, b) = P(a, m) * P(m, b)
-        // Q(a, b) = Q(a, m) * Q(m, b)
-        // T(a, b) = Q(m, b) * T(a, m) + P(a, m) * T(m, b)
-
-        // Compute T into a fresh Integer
-        let mut t = Integer::from(&q2 * &t1);
-        t += Integer::from(&p1 * &t2);
-
-        // Reuse p1 and q1 as the result to reduce allocations
-        p1 *= p2; // p1 = p1 * p2
-        q1 *= q2; // q1 = q1 * q2
-
-        (p1, q1, t)
-    }
+/// What the binary was asked to do.
+enum Mode {
+    /// Compute π or another constant (the default)
+    Pi,
+    /// `hex-digit`: extract hex digits at a position with BBP
+    HexDigit,
+    /// `cf`: continued fraction and convergents of the computed constant
+    ContinuedFraction,
+    /// `--stream`: print digits of π from a spigot until interrupted, or
+    /// until the digit count if one was given
+    Stream(Option<u64>),
 }
 
-/// Parse a digit specification like:
-/// "123", "1K", "10M", "2g", "132876K", "1e6", "3E7"
-///
-/// Supports suffixes:
-///   K = 1_000 (10^3)
-///   M = 1_000_000 (10^6)
-///   G = 1_000_000_000 (10^9)
-///   T = 1_000_000_000_000 (10^12)
-///
-/// Also supports scientific notation: "<int>e<int>", e.g. "1e6".
-///
-/// Returns number of digits as u32 (max ~4.29e9).
-fn parse_digit_spec(spec: &str) -> Result<u32, String> {
-    let s = spec.trim();
-    if s.is_empty() {
-        return Err("Empty digits specification".to_string());
-    }
-
-    // 1) Scientific notation: "<int>e<int>" or "<int>E<int>"
-    if let Some(pos) = s.find(|c| c == 'e' || c == 'E') {
-        let (mantissa_str, exp_str_with_e) = s.split_at(pos);
-        let exp_str = &exp_str_with_e[1..]; // skip 'e' or 'E'
-
-        if mantissa_str.is_empty() || exp_str.is_empty() {
-            return Err(format!("Invalid scientific notation: \"{}\"", spec));
-        }
-
-        let mantissa: u64 = mantissa_str
-            .parse()
-            .map_err(|_| format!("Invalid mantissa in \"{}\"", spec))?;
-        let exp: u32 = exp_str
-            .parse()
-            .map_err(|_| format!("Invalid exponent in \"{}\"", spec))?;
-
-        let multiplier = 10u64
-            .checked_pow(exp)
-            .ok_or_else(|| format!("Exponent too large in \"{}\"", spec))?;
-
-        let value = mantissa
-            .checked_mul(multiplier)
-            .ok_or_else(|| format!("Digits value overflow for \"{}\"", spec))?;
-
-        if value > u32::MAX as u64 {
-            return Err(format!(
-                "Too many digits ({}), max supported is {}",
-                value,
-                u32::MAX
-            ));
-        }
-
-        return Ok(value as u32);
-    }
-
-    // 2) Suffix-based notation: K, M, G, T
-    let s_upper = s.to_uppercase();
-    let (num_str, multiplier): (&str, u64) = if s_upper.ends_with('K') {
-        (&s_upper[..s_upper.len() - 1], 1_000)
-    } else if s_upper.ends_with('M') {
-        (&s_upper[..s_upper.len() - 1], 1_000_000)
-    } else if s_upper.ends_with('G') {
-        (&s_upper[..s_upper.len() - 1], 1_000_000_000)
-    } else if s_upper.ends_with('T') {
-        (&s_upper[..s_upper.len() - 1], 1_000_000_000_000)
-    } else {
-        (s_upper.as_str(), 1)
-    };
-
-    if num_str.is_empty() {
-        return Err(format!("Missing number before suffix in \"{}\"", spec));
-    }
-
-    let base: u64 = num_str
-        .parse()
-        .map_err(|_| format!("Invalid number part in \"{}\"", spec))?;
-
-    let value: u64 = base
-        .checked_mul(multiplier)
-        .ok_or_else(|| format!("Digits value overflow for \"{}\"", spec))?;
-
-    if value > u32::MAX as u64 {
-        return Err(format!(
-            "Too many digits ({}), max supported is {}",
-            value,
-            u32::MAX
-        ));
-    }
-
-    Ok(value as u32)
+/// Parsed command-line options.
+struct CliArgs {
+    mode: Mode,
+    constant: Constant,
+    digits: u64,
+    threads: usize,
+    checkpoint_dir: Option<PathBuf>,
+    extend_from: Option<PathBuf>,
+    save_state: Option<PathBuf>,
+    progress: Option<ProgressMode>,
+    report: Option<PathBuf>,
+    output: Option<PathBuf>,
+    chunk_size: Option<u64>,
+    base: u32,
+    algorithm: Algorithm,
+    formula: Option<Formula>,
+    series: Option<Series>,
+    verify: bool,
+    cross_check: bool,
+    position: u64,
+    /// Hex digits for `hex-digit`, partial quotients for `cf`
+    count: Option<usize>,
 }
 
-/// Get digits from CLI arguments.
+/// Parse CLI arguments.
 /// Supported forms:
 ///   - <prog>                 -> default (100000)
 ///   - <prog> 12345
@@ -181,9 +75,46 @@ fn parse_digit_spec(spec: &str) -> Result<u32, String> {
 ///   - <prog> -d 132876K
 ///   - <prog> -c 1e6
 ///   - <prog> -d 1E6
-fn get_digits_from_args() -> Result<u32, String> {
+///   - <prog> 10M --threads 8
+///   - <prog> 100M --checkpoint-dir ckpt
+///   - <prog> 100M --save-state pi.state
+///   - <prog> 200M --extend-from pi.state  (only sums the new terms)
+///   - <prog> 100M --progress        (or --progress=json)
+///   - <prog> 10M --report run.json
+///   - <prog> 1G --output pi.txt --chunk-size 100M
+///   - <prog> 1M --base 16           (digits counted in the output base)
+///   - <prog> 1M --algorithm agm     (Gauss–Legendre instead of Chudnovsky)
+///   - <prog> 1M --algorithm machin --formula takano
+///   - <prog> 1M --formula 4:5,-1:239  (π/4 = Σ c·arctan(1/x))
+///   - <prog> 1M --series ramanujan  (or d67, d43, d19)
+///   - <prog> 1M --verify            (check the last hex digits with BBP)
+///   - <prog> 1M --cross-check       (recompute with the other algorithm)
+///   - <prog> 1M --constant e
+///   - <prog> hex-digit --position 1M [--count 16]
+///   - <prog> cf 1K [--count 20]     (continued fraction and convergents)
+///   - <prog> --stream [1M]          (spigot digits until interrupted)
+fn parse_args() -> Result<CliArgs, String> {
     let mut args = std::env::args().skip(1); // skip program name
     let mut digit_spec: Option<String> = None;
+    let mut threads: usize = 1;
+    let mut checkpoint_dir: Option<PathBuf> = None;
+    let mut extend_from: Option<PathBuf> = None;
+    let mut save_state: Option<PathBuf> = None;
+    let mut progress: Option<ProgressMode> = None;
+    let mut report: Option<PathBuf> = None;
+    let mut output: Option<PathBuf> = None;
+    let mut chunk_size: Option<u64> = None;
+    let mut base: u32 = 10;
+    let mut algorithm = Algorithm::Chudnovsky;
+    let mut formula: Option<Formula> = None;
+    let mut series: Option<Series> = None;
+    let mut verify = false;
+    let mut cross_check = false;
+    let mut mode = Mode::Pi;
+    let mut constant = Constant::Pi;
+    let mut position: Option<u64> = None;
+    let mut count: Option<usize> = None;
+    let mut stream = false;
 
     while let Some(arg) = args.next() {
         match arg.as_str() {
@@ -193,6 +124,123 @@ fn get_digits_from_args() -> Result<u32, String> {
                     .ok_or_else(|| format!("Flag {} requires a value", arg))?;
                 digit_spec = Some(value);
             }
+            "--threads" | "-t" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| format!("Flag {} requires a value", arg))?;
+                threads = value
+                    .parse()
+                    .ok()
+                    .filter(|&n| n > 0)
+                    .ok_or_else(|| format!("Invalid thread count \"{}\"", value))?;
+            }
+            "--checkpoint-dir" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| format!("Flag {} requires a value", arg))?;
+                checkpoint_dir = Some(PathBuf::from(value));
+            }
+            "--extend-from" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| format!("Flag {} requires a value", arg))?;
+                extend_from = Some(PathBuf::from(value));
+            }
+            "--save-state" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| format!("Flag {} requires a value", arg))?;
+                save_state = Some(PathBuf::from(value));
+            }
+            "--report" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| format!("Flag {} requires a value", arg))?;
+                report = Some(PathBuf::from(value));
+            }
+            "--output" | "-o" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| format!("Flag {} requires a value", arg))?;
+                output = Some(PathBuf::from(value));
+            }
+            "--chunk-size" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| format!("Flag {} requires a value", arg))?;
+                chunk_size = Some(parse_digit_spec(&value)?);
+            }
+            "--base" | "-b" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| format!("Flag {} requires a value", arg))?;
+                base = value
+                    .parse()
+                    .ok()
+                    .filter(|b| (2..=36).contains(b))
+                    .ok_or_else(|| format!("Invalid base \"{}\", expected 2..=36", value))?;
+            }
+            "--algorithm" | "-a" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| format!("Flag {} requires a value", arg))?;
+                algorithm = Algorithm::from_name(&value).ok_or_else(|| {
+                    format!(
+                        "Unknown algorithm \"{}\", expected chudnovsky, agm or machin",
+                        value
+                    )
+                })?;
+            }
+            "--formula" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| format!("Flag {} requires a value", arg))?;
+                formula = Some(Formula::parse(&value)?);
+            }
+            "--series" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| format!("Flag {} requires a value", arg))?;
+                series = Some(Series::by_name(&value).ok_or_else(|| {
+                    let names: Vec<&str> = Series::ALL.iter().map(|s| s.name).collect();
+                    format!(
+                        "Unknown series \"{}\", expected {}",
+                        value,
+                        names.join(", ")
+                    )
+                })?);
+            }
+            "--constant" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| format!("Flag {} requires a value", arg))?;
+                constant = Constant::parse(&value)?;
+            }
+            "--verify" => verify = true,
+            "--cross-check" => cross_check = true,
+            "hex-digit" => mode = Mode::HexDigit,
+            "cf" => mode = Mode::ContinuedFraction,
+            "--stream" => stream = true,
+            "--position" | "-p" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| format!("Flag {} requires a value", arg))?;
+                position = Some(parse_digit_spec(&value)?);
+            }
+            "--count" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| format!("Flag {} requires a value", arg))?;
+                count = Some(
+                    value
+                        .parse()
+                        .ok()
+                        .filter(|&n| n > 0)
+                        .ok_or_else(|| format!("Invalid count \"{}\"", value))?,
+                );
+            }
+            "--progress" | "--progress=human" => progress = Some(ProgressMode::Human),
+            "--progress=json" => progress = Some(ProgressMode::Json),
             // First bare argument that is not a flag: treat as digits spec
             _ if !arg.starts_with('-') && digit_spec.is_none() => {
                 digit_spec = Some(arg);
@@ -203,17 +251,82 @@ fn get_digits_from_args() -> Result<u32, String> {
     }
 
     // Default if nothing given
-    let default_digits: u32 = 100_000;
+    let default_digits: u64 = 100_000;
+
+    let digits = match &digit_spec {
+        Some(spec) => parse_digit_spec(spec)?,
+        None => default_digits,
+    };
+
+    if stream {
+        if constant != Constant::Pi {
+            return Err("--stream only computes π".to_string());
+        }
+        mode = Mode::Stream(digit_spec.is_some().then_some(digits));
+    }
+
+    if chunk_size.is_some() && output.is_none() {
+        return Err("--chunk-size requires --output".to_string());
+    }
+    if chunk_size == Some(0) {
+        return Err("Chunk size must be at least 1 digit".to_string());
+    }
+
+    // A formula implies the Machin-like engine
+    if formula.is_some() && algorithm == Algorithm::Chudnovsky {
+        algorithm = Algorithm::Machin;
+    } else if formula.is_some() && algorithm != Algorithm::Machin {
+        return Err("--formula only applies to --algorithm machin".to_string());
+    }
+
+    if series.is_some() && algorithm != Algorithm::Chudnovsky {
+        return Err("--series only applies to --algorithm chudnovsky".to_string());
+    }
 
-    match digit_spec {
-        Some(spec) => parse_digit_spec(&spec),
-        None => Ok(default_digits),
+    if constant != Constant::Pi {
+        let pi_only = [
+            (algorithm != Algorithm::Chudnovsky, "--algorithm"),
+            (series.is_some(), "--series"),
+            (formula.is_some(), "--formula"),
+            (verify, "--verify"),
+            (cross_check, "--cross-check"),
+        ];
+        if let Some((_, flag)) = pi_only.iter().find(|(set, _)| *set) {
+            return Err(format!("{} only applies to π", flag));
+        }
     }
+
+    let position = match (&mode, position) {
+        (Mode::HexDigit, None) => return Err("hex-digit requires --position N".to_string()),
+        (_, position) => position.unwrap_or(1),
+    };
+
+    Ok(CliArgs {
+        mode,
+        constant,
+        digits,
+        threads,
+        checkpoint_dir,
+        extend_from,
+        save_state,
+        progress,
+        report,
+        output,
+        chunk_size,
+        base,
+        algorithm,
+        formula,
+        series,
+        verify,
+        cross_check,
+        position,
+        count,
+    })
 }
 
 fn main() {
-    // Read digits from CLI
-    let digits_u32 = match get_digits_from_args() {
+    // Read options from CLI
+    let args = match parse_args() {
         Ok(d) => d,
         Err(e) => {
             eprintln!("Error: {}", e);
@@ -223,59 +336,267 @@ fn main() {
             eprintln!("  cargo run --release -- --calculate 1K");
             eprintln!("  cargo run --release -- --digits 10M");
             eprintln!("  cargo run --release -- 1e6");
+            eprintln!("  cargo run --release -- 10M --threads 8");
+            eprintln!("  cargo run --release -- 100M --checkpoint-dir ckpt");
+            eprintln!("  cargo run --release -- 200M --extend-from pi.state --save-state pi.state");
+            eprintln!("  cargo run --release -- 100M --progress=json");
+            eprintln!("  cargo run --release -- 10M --report run.json");
+            eprintln!("  cargo run --release -- 1G --output pi.txt --chunk-size 100M");
+            eprintln!("  cargo run --release -- 1M --base 16");
+            eprintln!("  cargo run --release -- 1M --algorithm agm");
+            eprintln!("  cargo run --release -- 1M --algorithm machin --formula takano");
+            eprintln!("  cargo run --release -- 1M --formula 4:5,-1:239");
+            eprintln!("  cargo run --release -- 1M --series ramanujan");
+            eprintln!("  cargo run --release -- 1M --verify");
+            eprintln!("  cargo run --release -- 1M --cross-check");
+            eprintln!("  cargo run --release -- 1M --constant e");
+            eprintln!("  cargo run --release -- hex-digit --position 1M");
+            eprintln!("  cargo run --release -- cf 1K --count 20");
+            eprintln!("  cargo run --release -- --stream");
             std::process::exit(1);
         }
     };
 
-    let digits: usize = digits_u32 as usize;
+    let outcome = match args.mode {
+        Mode::Pi => run_pi(args),
+        Mode::HexDigit => run_hex_digit(&args),
+        Mode::ContinuedFraction => run_cf(args),
+        Mode::Stream(limit) => run_stream(args.base, limit),
+    };
+    if let Err(e) = outcome {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+}
 
-    println!(
-        "Calculating π to {} digits (Rust + Rug, Chudnovsky)...",
-        digits
-    );
-    let start = Instant::now();
+/// Library options for the computation modes (`run_pi` and `run_cf`).
+fn options(args: &CliArgs) -> ComputeOptions {
+    ComputeOptions {
+        threads: args.threads,
+        checkpoint_dir: args.checkpoint_dir.clone(),
+        extend_from: args.extend_from.clone(),
+        save_state: args.save_state.clone(),
+        progress: args.progress.map(|mode| Arc::new(Progress::new(mode))),
+        base: args.base,
+        algorithm: args.algorithm,
+        series: args.series.unwrap_or(Series::CHUDNOVSKY),
+        formula: args.formula.clone().unwrap_or_default(),
+    }
+}
 
-    // Each Chudnovsky term yields ~14 digits
-    let terms = digits / 14 + 1;
-    let (_p, q, t) = binary_split(0, terms as u64);
+/// Compute π to `args.digits` digits and write them out.
+fn run_pi(args: CliArgs) -> Result<(), String> {
+    let options = options(&args);
+    let (formula, series) = (&options.formula, options.series);
+    let method = match (args.constant, args.algorithm) {
+        (Constant::Pi, Algorithm::Machin) => {
+            format!("{} ({})", args.algorithm.description(), formula.name)
+        }
+        (Constant::Pi, Algorithm::Chudnovsky) if series != Series::CHUDNOVSKY => {
+            format!("{} series", series.description)
+        }
+        (Constant::Pi, algorithm) => algorithm.description().to_string(),
+        (constant, _) => constant.method().to_string(),
+    };
+    if args.base == 10 {
+        println!(
+            "Calculating {} to {} digits (Rust + Rug, {})...",
+            args.constant, args.digits, method
+        );
+    } else {
+        println!(
+            "Calculating {} to {} base-{} digits (Rust + Rug, {})...",
+            args.constant, args.digits, args.base, method
+        );
+    }
 
-    // Compute required precision in bits:
-    // bits ≈ digits * log2(10) + safety_margin
-    let bits_per_digit = 3.321_928_094_887_362_f64; // log2(10)
-    let extra_bits = 256.0; // safety margin
-    let precision: u32 = (digits as f64 * bits_per_digit + extra_bits) as u32;
+    let result = compute_constant(args.constant, args.digits, &options)?;
 
-    // π = (Q * 426880 * sqrt(10005)) / T
-    let sqrt_10005 = Float::with_val(precision, 10005).sqrt();
+    println!("Time: {:.4}s", result.elapsed.as_secs_f64());
+    if let Some(series) = result.series {
+        println!(
+            "Series: {}, {} terms, {:.2} digits per term",
+            series.name,
+            result.terms,
+            series.digits_per_term()
+        );
+    }
 
-    // Numerator as Integer first, then convert once to Float
-    let q_times_c = Integer::from(426880) * &q;
-    let numerator = Float::with_val(precision, q_times_c) * sqrt_10005;
-    let denominator = Float::with_val(precision, &t);
-    let pi = numerator / denominator;
+    let progress = options.progress.as_deref();
+    let mut timings = result.timings.clone();
+    let mut enter = |phase| {
+        timings.enter(phase);
+        if let Some(progress) = progress {
+            progress.phase(phase);
+        }
+    };
 
-    // Scale by 10^digits using Integer -> Float (explicit, safe)
-    let scale_int = Integer::from(10).pow(digits_u32);
-    let scale = Float::with_val(precision, &scale_int);
-    let pi_scaled = &pi * scale;
+    enter(Phase::RadixConversion);
+    let expansion = result.expansion_string();
+
+    enter(Phase::Output);
+    match &args.output {
+        Some(path) => {
+            let files = output::write_to_path(&expansion, result.digits, path, args.chunk_size)?;
+            match files.as_slice() {
+                [file] => println!("Wrote digits to {}", file.display()),
+                [chunks @ .., index] => println!(
+                    "Wrote {} chunk files, index: {}",
+                    chunks.len(),
+                    index.display()
+                ),
+                [] => {}
+            }
+        }
+        None => output::write_digits(
+            &mut BufWriter::new(io::stdout().lock()),
+            &expansion,
+            result.digits,
+        )
+        .map_err(|e| format!("Cannot write to stdout: {}", e))?,
+    }
 
-    // Truncate instead of round: floor first, then convert to Integer
-    let pi_floor = pi_scaled.floor();
-    let pi_integer = pi_floor
-        .to_integer()
-        .expect("Failed to convert floor(pi * 10^digits) to Integer");
+    let verification = if args.verify {
+        enter(Phase::Verify);
+        let v = verify_bbp(&result)?;
+        let range = format!("hex digits {}..={}", v.position, v.last_position());
+        if v.passed() {
+            println!("Verify: PASS, {} match BBP ({})", range, v.expected);
+        } else {
+            println!(
+                "Verify: FAIL, {} are {} but BBP gives {}",
+                range, v.computed, v.expected
+            );
+        }
+        Some(v)
+    } else {
+        None
+    };
 
-    println!("Time: {:.4}s", start.elapsed().as_secs_f64());
+    let cross = if args.cross_check {
+        enter(Phase::CrossCheck);
+        let c = cross_check(&result, &expansion)?;
+        match c.first_difference {
+            None => println!(
+                "Cross-check: PASS, all {} digits agree with {}",
+                c.digits, c.algorithm
+            ),
+            Some(0) => println!(
+                "Cross-check: FAIL, integer part differs from {}",
+                c.algorithm
+            ),
+            Some(pos) => println!(
+                "Cross-check: FAIL, first differing digit at position {} ({})",
+                pos, c.algorithm
+            ),
+        }
+        Some(c)
+    } else {
+        None
+    };
+    timings.stop();
 
-    // Convert to string and insert decimal point after first digit: 3.<digits>
-    let mut pi_str = pi_integer.to_string();
+    if let Some(progress) = progress {
+        progress.finish();
+    }
 
-    // Ensure at least digits + 1 characters ("3" + digits decimals)
-    if pi_str.len() <= digits {
-        pi_str = format!("{:0>width$}", pi_str, width = digits + 1);
+    if let Some(path) = &args.report {
+        RunReport::new(&result, args.threads, timings).write(path)?;
     }
 
-    let (int_part, frac_part) = pi_str.split_at(1);
-    println!("{}.{}", int_part, &frac_part[..digits]);
+    if verification.is_some_and(|v| !v.passed()) {
+        return Err("Verification against BBP failed".to_string());
+    }
+    if cross.is_some_and(|c| !c.passed()) {
+        return Err("Cross-check failed".to_string());
+    }
+    Ok(())
 }
 
+/// Print `args.count` hex digits of π starting at `args.position` (BBP).
+fn run_hex_digit(args: &CliArgs) -> Result<(), String> {
+    let start = Instant::now();
+    let digits = bbp::hex_digits_at(args.position, args.count.unwrap_or(16))?;
+    println!(
+        "Hex digits of π at position {} (BBP, {:.4}s):",
+        args.position,
+        start.elapsed().as_secs_f64()
+    );
+    println!("{}", digits);
+    Ok(())
+}
+
+/// Print digits of π from the spigot as they are produced.
+fn run_stream(base: u32, limit: Option<u64>) -> Result<(), String> {
+    let written = output::write_stream(
+        &mut io::stdout().lock(),
+        spigot::Spigot::new(base),
+        base,
+        limit,
+    );
+    match written {
+        // The reader went away (e.g. `| head -c 1000`): a normal way to stop
+        Err(e) if e.kind() == io::ErrorKind::BrokenPipe => Ok(()),
+        written => written.map_err(|e| format!("Cannot write to stdout: {}", e)),
+    }
+}
+
+/// Convergents listed by `cf` without `--count`.
+const CONVERGENTS_SHOWN: usize = 20;
+
+/// Print the continued fraction of the constant that `args.digits` digits
+/// guarantee, with its convergents and their error bounds.
+fn run_cf(args: CliArgs) -> Result<(), String> {
+    let options = options(&args);
+    let result = compute_constant(args.constant, args.digits, &options)?;
+    if let Some(progress) = options.progress.as_deref() {
+        progress.finish();
+    }
+
+    let start = Instant::now();
+    // One quotient past the count tightens the last convergent's bound
+    let fetched = cf::partial_quotients(&result, args.count.map(|n| n.saturating_add(1)));
+    let quotients = &fetched[..args.count.unwrap_or(fetched.len()).min(fetched.len())];
+    println!(
+        "Continued fraction of {} from {} digits ({} partial quotients, {:.4}s):",
+        args.constant,
+        args.digits,
+        quotients.len(),
+        (result.elapsed + start.elapsed()).as_secs_f64()
+    );
+
+    let mut line = String::from("[");
+    for (i, a) in quotients.iter().enumerate() {
+        match i {
+            0 => line.push_str(&a.to_string()),
+            1 => line.push_str(&format!("; {}", a)),
+            _ => line.push_str(&format!(", {}", a)),
+        }
+    }
+    line.push(']');
+    println!("{}", line);
+
+    // Convergents grow to the size of the input, so list the first few
+    // unless asked for a count; the quotient after the last one shown
+    // tightens its bound
+    let shown = args.count.unwrap_or(CONVERGENTS_SHOWN).min(quotients.len());
+    let convergents = cf::convergents(&fetched[..(shown + 1).min(fetched.len())]);
+    if shown < quotients.len() {
+        println!(
+            "Convergents (first {} of {}, --count N lists more):",
+            shown,
+            quotients.len()
+        );
+    } else {
+        println!("Convergents:");
+    }
+    for c in &convergents[..shown] {
+        println!(
+            "  {}/{}  error < {}",
+            c.p,
+            c.q,
+            c.error_bound.to_string_radix_round(10, Some(3), Round::Up)
+        );
+    }
+    Ok(())
+}
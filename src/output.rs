@@ -0,0 +1,139 @@
+//! Buffered, optionally chunked output of the digit expansion.
+//!
+//! All writers take the string from `PiResult::expansion_string` (integer
+//! part followed by the fractional digits) and write slices of it, so the
+//! expansion is held in memory once. `write_stream` is the exception: it
+//! prints digits one at a time as an unbounded spigot produces them.
+//!
+//! Chunked output splits the fractional digits into files `<path>.0000`,
+//! `<path>.0001`, ... of `chunk_digits` digits each. The first chunk starts
+//! with the integer part and "." and the last one ends with a newline, so
+//! concatenating the chunks in order gives the same bytes as unchunked
+//! output. `<path>.index` lists each chunk with the (1-based, inclusive)
+//! fractional digit positions it holds.
+
+use rug::Integer;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+/// Buffer size for digit files; large enough to keep syscalls rare.
+const BUF_SIZE: usize = 1 << 20;
+
+/// Write "<integer part>.<fraction>\n" to `w`, where the last `digits`
+/// characters of `expansion` are the fraction.
+pub fn write_digits(w: &mut impl Write, expansion: &str, digits: u64) -> io::Result<()> {
+    let (int_part, frac_part) = expansion.split_at(expansion.len() - digits as usize);
+    w.write_all(int_part.as_bytes())?;
+    w.write_all(b".")?;
+    w.write_all(frac_part.as_bytes())?;
+    w.write_all(b"\n")?;
+    w.flush()
+}
+
+/// Write digits as they are produced: `digits` yields the integer part
+/// followed by fractional digits in `base`, printed as "<integer>." and then
+/// one character per digit, flushing after each so a reader sees them
+/// immediately. With `limit`, stops after that many fractional digits and
+/// ends the line like `write_digits`; otherwise runs until `digits` ends.
+pub fn write_stream(
+    w: &mut impl Write,
+    mut digits: impl Iterator<Item = u32>,
+    base: u32,
+    limit: Option<u64>,
+) -> io::Result<()> {
+    let Some(integer) = digits.next() else {
+        return Ok(());
+    };
+    write!(
+        w,
+        "{}.",
+        Integer::from(integer).to_string_radix(base as i32)
+    )?;
+    w.flush()?;
+
+    let limit = limit.map_or(usize::MAX, |l| usize::try_from(l).unwrap_or(usize::MAX));
+    for digit in digits.take(limit) {
+        let c = char::from_digit(digit, base).expect("digit below the base");
+        w.write_all(&[c as u8])?;
+        w.flush()?;
+    }
+    w.write_all(b"\n")?;
+    w.flush()
+}
+
+/// Write the expansion to `path`, or to chunk files next to it when
+/// `chunk_digits` is set. Returns the files written.
+pub fn write_to_path(
+    expansion: &str,
+    digits: u64,
+    path: &Path,
+    chunk_digits: Option<u64>,
+) -> Result<Vec<PathBuf>, String> {
+    let err = |p: &Path, e: io::Error| format!("Cannot write {}: {}", p.display(), e);
+
+    let Some(chunk_digits) = chunk_digits else {
+        let file = File::create(path).map_err(|e| err(path, e))?;
+        write_digits(
+            &mut BufWriter::with_capacity(BUF_SIZE, file),
+            expansion,
+            digits,
+        )
+        .map_err(|e| err(path, e))?;
+        return Ok(vec![path.to_path_buf()]);
+    };
+    if chunk_digits == 0 {
+        return Err("Chunk size must be at least 1 digit".to_string());
+    }
+
+    let (int_part, frac_part) = expansion
+        .as_bytes()
+        .split_at(expansion.len() - digits as usize);
+    let chunk = chunk_digits.min(frac_part.len().max(1) as u64) as usize;
+    let count = frac_part.len().div_ceil(chunk).max(1);
+    let width = count.saturating_sub(1).to_string().len().max(4);
+
+    let index_path = suffixed(path, "index");
+    let mut index = String::new();
+    index.push_str("# pi_calculator digit index: file first_digit last_digit\n");
+    index.push_str(&format!("digits={}\n", frac_part.len()));
+    index.push_str(&format!("chunk_size={}\n", chunk_digits));
+
+    let mut files = Vec::with_capacity(count + 1);
+    for i in 0..count {
+        let start = i * chunk;
+        let end = (start + chunk).min(frac_part.len());
+        let chunk_path = suffixed(path, &format!("{:0width$}", i, width = width));
+
+        let file = File::create(&chunk_path).map_err(|e| err(&chunk_path, e))?;
+        let mut w = BufWriter::with_capacity(BUF_SIZE, file);
+        let written = (|| {
+            if i == 0 {
+                w.write_all(int_part)?;
+                w.write_all(b".")?;
+            }
+            w.write_all(&frac_part[start..end])?;
+            if i + 1 == count {
+                w.write_all(b"\n")?;
+            }
+            w.flush()
+        })();
+        written.map_err(|e| err(&chunk_path, e))?;
+
+        let name = chunk_path.file_name().unwrap_or_default().to_string_lossy();
+        index.push_str(&format!("{} {} {}\n", name, start + 1, end));
+        files.push(chunk_path);
+    }
+
+    std::fs::write(&index_path, index).map_err(|e| err(&index_path, e))?;
+    files.push(index_path);
+    Ok(files)
+}
+
+/// `path` with ".<suffix>" appended to its file name.
+fn suffixed(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".");
+    name.push(suffix);
+    PathBuf::from(name)
+}
@@ -0,0 +1,241 @@
+//! Progress reporting on stderr.
+//!
+//! A run goes through the phases in `Phase`. Only the series phase can report
+//! how far along it is, since the others are single MPFR/GMP calls; for those
+//! the report is the phase start and the time spent so far.
+
+use std::fmt;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Minimum time between two progress lines within a phase.
+const REPORT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Stage of a computation, in the order they run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    /// Binary splitting of the series
+    Series,
+    /// Square root in the series constant (sqrt(10005) for Chudnovsky), or
+    /// the Newton iteration of an algebraic constant
+    Sqrt,
+    /// Gauss–Legendre iteration (`Algorithm::Agm` instead of the above)
+    Agm,
+    /// Final Float division, scaling and truncation
+    Division,
+    /// Integer -> digit string
+    RadixConversion,
+    /// Writing digits out
+    Output,
+    /// Checking the result against independent digits
+    Verify,
+    /// Recomputing the result with a second algorithm
+    CrossCheck,
+}
+
+impl Phase {
+    pub fn name(self) -> &'static str {
+        match self {
+            Phase::Series => "series",
+            Phase::Sqrt => "sqrt",
+            Phase::Agm => "agm",
+            Phase::Division => "division",
+            Phase::RadixConversion => "radix_conversion",
+            Phase::Output => "output",
+            Phase::Verify => "verify",
+            Phase::CrossCheck => "cross_check",
+        }
+    }
+}
+
+impl fmt::Display for Phase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Wall time spent in each phase, in the order the phases first ran. A
+/// phase entered again (e.g. a division after π and another after the AGM)
+/// adds to its earlier time.
+#[derive(Debug, Clone, Default)]
+pub struct PhaseTimings {
+    finished: Vec<(Phase, Duration)>,
+    current: Option<(Phase, Instant)>,
+}
+
+impl PhaseTimings {
+    /// Start timing `phase`, stopping the one in progress.
+    pub fn enter(&mut self, phase: Phase) {
+        self.stop();
+        self.current = Some((phase, Instant::now()));
+    }
+
+    /// Stop timing the phase in progress, if any.
+    pub fn stop(&mut self) {
+        if let Some((phase, start)) = self.current.take() {
+            match self.finished.iter_mut().find(|(p, _)| *p == phase) {
+                Some((_, d)) => *d += start.elapsed(),
+                None => self.finished.push((phase, start.elapsed())),
+            }
+        }
+    }
+
+    /// Finished phases with their durations.
+    pub fn phases(&self) -> &[(Phase, Duration)] {
+        &self.finished
+    }
+
+    /// Sum of all finished phases.
+    pub fn total(&self) -> Duration {
+        self.finished.iter().map(|&(_, d)| d).sum()
+    }
+}
+
+/// How progress is written to stderr.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProgressMode {
+    /// One line per update, meant for a terminal
+    Human,
+    /// One JSON object per line
+    Json,
+}
+
+#[derive(Debug)]
+struct State {
+    phase: Option<Phase>,
+    phase_start: Instant,
+    last_report: Option<Instant>,
+    terms_done: u64,
+    terms_total: u64,
+    work_done: f64,
+    work_total: f64,
+}
+
+/// Thread-safe progress reporter shared by all binary-splitting threads.
+#[derive(Debug)]
+pub struct Progress {
+    mode: ProgressMode,
+    start: Instant,
+    state: Mutex<State>,
+}
+
+impl Progress {
+    pub fn new(mode: ProgressMode) -> Progress {
+        let now = Instant::now();
+        Progress {
+            mode,
+            start: now,
+            state: Mutex::new(State {
+                phase: None,
+                phase_start: now,
+                last_report: None,
+                terms_done: 0,
+                terms_total: 0,
+                work_done: 0.0,
+                work_total: 0.0,
+            }),
+        }
+    }
+
+    /// Enter `phase`, closing the previous one.
+    pub fn phase(&self, phase: Phase) {
+        let mut state = self.state.lock().unwrap();
+        self.end_phase(&state);
+        state.phase = Some(phase);
+        state.phase_start = Instant::now();
+        state.last_report = None;
+
+        let elapsed = self.start.elapsed().as_secs_f64();
+        match self.mode {
+            ProgressMode::Human => eprintln!("[{:>8.2}s] {}...", elapsed, phase),
+            ProgressMode::Json => eprintln!(
+                "{{\"event\":\"phase\",\"phase\":\"{}\",\"elapsed\":{:.3}}}",
+                phase, elapsed
+            ),
+        }
+    }
+
+    /// Close the last phase and report the total time.
+    pub fn finish(&self) {
+        let mut state = self.state.lock().unwrap();
+        self.end_phase(&state);
+        state.phase = None;
+
+        let elapsed = self.start.elapsed().as_secs_f64();
+        match self.mode {
+            ProgressMode::Human => eprintln!("[{:>8.2}s] done", elapsed),
+            ProgressMode::Json => {
+                eprintln!("{{\"event\":\"done\",\"elapsed\":{:.3}}}", elapsed)
+            }
+        }
+    }
+
+    fn end_phase(&self, state: &State) {
+        let Some(phase) = state.phase else { return };
+        let took = state.phase_start.elapsed().as_secs_f64();
+        match self.mode {
+            ProgressMode::Human => eprintln!(
+                "[{:>8.2}s] {} took {:.2}s",
+                self.start.elapsed().as_secs_f64(),
+                phase,
+                took
+            ),
+            ProgressMode::Json => eprintln!(
+                "{{\"event\":\"phase_end\",\"phase\":\"{}\",\"seconds\":{:.3}}}",
+                phase, took
+            ),
+        }
+    }
+
+    /// Set the size of the series: its term count and total estimated work.
+    pub fn series_total(&self, terms: u64, work: f64) {
+        let mut state = self.state.lock().unwrap();
+        state.terms_total = terms;
+        state.work_total = work;
+    }
+
+    /// Record that `terms` more terms were merged at an estimated cost of
+    /// `work`; either may be zero.
+    pub fn series_advance(&self, terms: u64, work: f64) {
+        let mut state = self.state.lock().unwrap();
+        state.terms_done += terms;
+        state.work_done += work;
+
+        let now = Instant::now();
+        if state
+            .last_report
+            .is_some_and(|t| now.duration_since(t) < REPORT_INTERVAL)
+        {
+            return;
+        }
+        state.last_report = Some(now);
+
+        let fraction = if state.work_total > 0.0 {
+            (state.work_done / state.work_total).min(1.0)
+        } else {
+            0.0
+        };
+        let phase_elapsed = state.phase_start.elapsed().as_secs_f64();
+        let eta = (fraction > 0.0).then(|| phase_elapsed * (1.0 - fraction) / fraction);
+
+        let elapsed = self.start.elapsed().as_secs_f64();
+        match self.mode {
+            ProgressMode::Human => eprintln!(
+                "[{:>8.2}s] series {:5.1}% (terms {}/{}), ETA {}",
+                elapsed,
+                fraction * 100.0,
+                state.terms_done,
+                state.terms_total,
+                eta.map_or("?".to_string(), |s| format!("{:.1}s", s))
+            ),
+            ProgressMode::Json => eprintln!(
+                "{{\"event\":\"progress\",\"phase\":\"series\",\"fraction\":{:.4},\"terms_done\":{},\"terms_total\":{},\"elapsed\":{:.3},\"eta\":{}}}",
+                fraction,
+                state.terms_done,
+                state.terms_total,
+                elapsed,
+                eta.map_or("null".to_string(), |s| format!("{:.3}", s))
+            ),
+        }
+    }
+}
@@ -0,0 +1,128 @@
+//! Machine-readable run reports for benchmarking.
+//!
+//! A report records what was computed, how long each phase took, peak memory
+//! and the library versions, as a single JSON object.
+
+use crate::PiResult;
+use crate::constants::Constant;
+use crate::progress::PhaseTimings;
+use std::ffi::CStr;
+use std::fs;
+use std::path::Path;
+
+/// Everything `--report` writes for one run.
+#[derive(Debug, Clone)]
+pub struct RunReport {
+    pub constant: String,
+    pub digits: u64,
+    pub base: u32,
+    pub algorithm: &'static str,
+    /// Series name and its digits per term, for series runs
+    pub series: Option<(&'static str, f64)>,
+    pub terms: u64,
+    pub precision_bits: u64,
+    pub threads: usize,
+    /// Phases of the computation plus any the caller timed afterwards
+    /// (radix conversion, output)
+    pub timings: PhaseTimings,
+    /// Peak resident set size, where the platform reports it
+    pub peak_rss_bytes: Option<u64>,
+}
+
+impl RunReport {
+    /// Build a report for `result`, with `timings` covering every phase of
+    /// the run, and sample peak RSS now.
+    pub fn new(result: &PiResult, threads: usize, timings: PhaseTimings) -> RunReport {
+        RunReport {
+            constant: result.constant.name(),
+            digits: result.digits,
+            base: result.base,
+            algorithm: match result.constant {
+                Constant::Pi => result.algorithm.name(),
+                constant => constant.method(),
+            },
+            series: result.series.map(|s| (s.name, s.digits_per_term())),
+            terms: result.terms,
+            precision_bits: result.precision,
+            threads,
+            timings,
+            peak_rss_bytes: peak_rss_bytes(),
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        let phases: Vec<String> = self
+            .timings
+            .phases()
+            .iter()
+            .map(|(phase, d)| format!("\"{}\":{:.6}", phase, d.as_secs_f64()))
+            .collect();
+        let (gmp, mpfr) = library_versions();
+
+        format!(
+            concat!(
+                "{{\n",
+                "  \"constant\": \"{}\",\n",
+                "  \"digits\": {},\n",
+                "  \"base\": {},\n",
+                "  \"algorithm\": \"{}\",\n",
+                "  \"series\": {},\n",
+                "  \"digits_per_term\": {},\n",
+                "  \"terms\": {},\n",
+                "  \"precision_bits\": {},\n",
+                "  \"threads\": {},\n",
+                "  \"phases\": {{{}}},\n",
+                "  \"total_seconds\": {:.6},\n",
+                "  \"peak_rss_bytes\": {},\n",
+                "  \"versions\": {{\"pi_calculator\":\"{}\",\"rug\":\"{}\",\"gmp\":\"{}\",\"mpfr\":\"{}\"}}\n",
+                "}}\n"
+            ),
+            self.constant,
+            self.digits,
+            self.base,
+            self.algorithm,
+            self.series
+                .map_or("null".to_string(), |(name, _)| format!("\"{}\"", name)),
+            self.series
+                .map_or("null".to_string(), |(_, dpt)| format!("{:.4}", dpt)),
+            self.terms,
+            self.precision_bits,
+            self.threads,
+            phases.join(","),
+            self.timings.total().as_secs_f64(),
+            self.peak_rss_bytes
+                .map_or("null".to_string(), |b| b.to_string()),
+            env!("CARGO_PKG_VERSION"),
+            env!("PI_RUG_VERSION"),
+            gmp,
+            mpfr
+        )
+    }
+
+    /// Write the JSON report to `path`.
+    pub fn write(&self, path: &Path) -> Result<(), String> {
+        fs::write(path, self.to_json())
+            .map_err(|e| format!("Cannot write report {}: {}", path.display(), e))
+    }
+}
+
+/// GMP and MPFR versions of the linked libraries.
+fn library_versions() -> (String, String) {
+    // SAFETY: both are static NUL-terminated strings owned by the C libraries.
+    unsafe {
+        let gmp = CStr::from_ptr(gmp_mpfr_sys::gmp::version);
+        let mpfr = CStr::from_ptr(gmp_mpfr_sys::mpfr::get_version());
+        (
+            gmp.to_string_lossy().into_owned(),
+            mpfr.to_string_lossy().into_owned(),
+        )
+    }
+}
+
+/// Peak resident set size (VmHWM) from /proc; None elsewhere.
+fn peak_rss_bytes() -> Option<u64> {
+    let status = fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|l| l.starts_with("VmHWM:"))?;
+    let kb: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kb * 1024)
+}
@@ -0,0 +1,190 @@
+//! Ramanujan–Sato type series for 1/π, summed by binary splitting.
+//!
+//! Every series here has the shape
+//!
+//!   S = Σ_k (±1)^k · a(k) · Π_{j=1..k} p(j)/q(j)
+//!   π = factor · √radicand · Q(0, N) / T(0, N)
+//!
+//! with p(k) a product of three linear factors, q(k) = k³ · q_scale and
+//! a(k) = a0 + a1·k. The Chudnovsky series is the d=163 member of the
+//! Heegner-number family
+//!
+//!   1/π = 12 Σ_k (-1)^k (6k)! (A + Bk) / ((3k)! (k!)³ C^(3k+3/2))
+//!
+//! where j((1+√-d)/2) = -C³; the smaller Heegner numbers converge more
+//! slowly but use smaller integers. Ramanujan's 1914 series
+//!
+//!   1/π = (2√2 / 9801) Σ_k (4k)! (1103 + 26390k) / ((k!)^4 396^(4k))
+//!
+//! fits the same shape with (4k)!/(k!)^4 in place of (6k)!/((3k)!(k!)³).
+//!
+//! `Series` implements `Hypergeometric`, so the sums run on the generic
+//! engine in `hypergeometric`.
+
+use crate::hypergeometric::Hypergeometric;
+use rug::Integer;
+
+/// Description of one series; see the module docs for the meaning of each
+/// field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Series {
+    /// Name as accepted by `--series`
+    pub name: &'static str,
+    /// Human-readable name for messages
+    pub description: &'static str,
+    /// p(k) = p_scale · Π (m·k - c) over (m, c) in `p_factors`
+    pub p_scale: u64,
+    pub p_factors: [(u64, u64); 3],
+    /// q(k) = k³ · q_scale
+    pub q_scale: u64,
+    /// a(k) = a0 + a1·k
+    pub a0: u64,
+    pub a1: u64,
+    /// Terms alternate in sign
+    pub alternating: bool,
+    /// π = factor.0 / factor.1 · √radicand · Q / T
+    pub factor: (u64, u64),
+    pub radicand: u64,
+}
+
+/// Chudnovsky-type series for Heegner number d with j = -C³:
+/// p(k) = (6k-5)(2k-1)(6k-1), q(k) = k³ C³/24, π = (C/12)·√C · Q/T
+/// (with the square factor of C taken out of the root).
+const fn heegner(
+    name: &'static str,
+    description: &'static str,
+    a: u64,
+    b: u64,
+    c: u64,
+    factor: u64,
+    radicand: u64,
+) -> Series {
+    Series {
+        name,
+        description,
+        p_scale: 1,
+        p_factors: [(6, 5), (2, 1), (6, 1)],
+        q_scale: c * c * c / 24,
+        a0: a,
+        a1: b,
+        alternating: true,
+        factor: (factor, 1),
+        radicand,
+    }
+}
+
+impl Series {
+    /// d=163: the default, about 14 digits per term.
+    pub const CHUDNOVSKY: Series = heegner(
+        "chudnovsky",
+        "Chudnovsky",
+        13591409,
+        545140134,
+        640320,
+        426880,
+        10005,
+    );
+    /// d=67: C = 5280, √5280 = 4√330.
+    pub const HEEGNER_67: Series = heegner("d67", "Heegner d=67", 10177, 261702, 5280, 1760, 330);
+    /// d=43: C = 960, √960 = 8√15.
+    pub const HEEGNER_43: Series = heegner("d43", "Heegner d=43", 789, 16254, 960, 640, 15);
+    /// d=19: C = 96, √96 = 4√6.
+    pub const HEEGNER_19: Series = heegner("d19", "Heegner d=19", 25, 342, 96, 32, 6);
+    /// Ramanujan 1914: (4k)!/(k!)^4 = Π 8(4j-1)(2j-1)(4j-3)/j³, π = 9801/4 · √2 · Q/T.
+    pub const RAMANUJAN: Series = Series {
+        name: "ramanujan",
+        description: "Ramanujan 1914",
+        p_scale: 8,
+        p_factors: [(4, 1), (2, 1), (4, 3)],
+        q_scale: 24591257856, // 396^4
+        a0: 1103,
+        a1: 26390,
+        alternating: false,
+        factor: (9801, 4),
+        radicand: 2,
+    };
+
+    /// All built-in series.
+    pub const ALL: [Series; 5] = [
+        Series::CHUDNOVSKY,
+        Series::RAMANUJAN,
+        Series::HEEGNER_67,
+        Series::HEEGNER_43,
+        Series::HEEGNER_19,
+    ];
+
+    /// Look up a built-in series by name ("d163" is the Chudnovsky series).
+    pub fn by_name(name: &str) -> Option<Series> {
+        let name = name.to_ascii_lowercase();
+        let name = if name == "d163" { "chudnovsky" } else { &name };
+        Series::ALL.into_iter().find(|s| s.name == name)
+    }
+
+    /// Decimal digits gained per term: log10 of the limit of q(k)/p(k).
+    pub fn digits_per_term(&self) -> f64 {
+        let p: f64 = self.p_factors.iter().map(|&(m, _)| m as f64).product();
+        (self.q_scale as f64 / (self.p_scale as f64 * p)).log10()
+    }
+
+    /// Number of terms needed for `digits` decimal digits; one extra term
+    /// covers the truncated remainder.
+    pub fn terms_for_digits(&self, digits: u64) -> u64 {
+        (digits as f64 / self.digits_per_term()).ceil() as u64 + 1
+    }
+}
+
+impl Hypergeometric for Series {
+    /// p(k) = p_scale · Π (m·k - c), and 1 for k = 0
+    fn p(&self, k: u64) -> Integer {
+        if k == 0 {
+            return Integer::from(1);
+        }
+        let mut p = Integer::from(self.p_scale);
+        for (m, c) in self.p_factors {
+            p *= m * k - c;
+        }
+        p
+    }
+
+    /// q(k) = k³ · q_scale, and 1 for k = 0
+    fn q(&self, k: u64) -> Integer {
+        if k == 0 {
+            return Integer::from(1);
+        }
+        Integer::from(k) * k * k * self.q_scale
+    }
+
+    /// a(k) = (±1)^k (a0 + a1·k)
+    fn a(&self, k: u64) -> Integer {
+        let a = Integer::from(self.a0) + Integer::from(self.a1) * k;
+        if self.alternating && k % 2 == 1 {
+            -a
+        } else {
+            a
+        }
+    }
+
+    /// Approximate size in bits of the factor term k adds to Q.
+    fn term_bits(&self, k: u64) -> f64 {
+        3.0 * (k.max(2) as f64).log2() + (self.q_scale as f64).log2()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ComputeOptions, compute_pi, compute_pi_with};
+
+    #[test]
+    fn series_match_chudnovsky() {
+        let chudnovsky = compute_pi(2000).unwrap();
+        for series in Series::ALL {
+            let options = ComputeOptions {
+                series,
+                ..ComputeOptions::default()
+            };
+            let pi = compute_pi_with(2000, &options).unwrap();
+            assert_eq!(pi.integer, chudnovsky.integer, "{}", series.name);
+        }
+    }
+}
@@ -0,0 +1,112 @@
+//! Gibbons' unbounded spigot for the digits of π.
+//!
+//! π is the limit of a composition of linear fractional transformations
+//! from the Leibniz-Euler series,
+//!
+//!   π = 2 + 1/3 (2 + 2/5 (2 + 3/7 (2 + ...)))
+//!
+//! and the state (q, r, t) holds the composed map x ↦ (q·x + r) / t. The
+//! rest of the composition always lies in [3, 4], so once (3q + r)/t and
+//! (4q + r)/t have the same integer part that digit is final: it is
+//! emitted and the map is rescaled by the base. Otherwise one more term is
+//! absorbed. Nothing bounds the number of digits up front; the cost is the
+//! growth of q, r and t, quadratic in the digits produced so far.
+//!
+//! Every digit is certain when it is produced, in any base (J. Gibbons,
+//! "Unbounded spigot algorithms for the digits of pi", 2006).
+
+use rug::Integer;
+
+/// Digits of π in `base`, forever: first the integer part (3), then one
+/// fractional digit per item.
+#[derive(Debug, Clone)]
+pub struct Spigot {
+    base: u32,
+    q: Integer,
+    r: Integer,
+    t: Integer,
+    k: u64,
+    /// Candidate digit floor((3q + r) / t)
+    n: Integer,
+    /// 2k + 1
+    l: u64,
+}
+
+impl Spigot {
+    pub fn new(base: u32) -> Spigot {
+        Spigot {
+            base,
+            q: Integer::from(1),
+            r: Integer::from(0),
+            t: Integer::from(1),
+            k: 1,
+            n: Integer::from(3),
+            l: 3,
+        }
+    }
+}
+
+impl Iterator for Spigot {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<u32> {
+        loop {
+            // Safe when (4q + r)/t still floors to n: 4q + r - t < n·t
+            let upper = Integer::from(&self.q * 4u32) + &self.r - &self.t;
+            if upper < Integer::from(&self.n * &self.t) {
+                let digit = self.n.to_u32().expect("spigot digit fits in u32");
+
+                // x ↦ base·(x - n): r = base·(r - n·t), n = floor(base·(3q + r)/t) - base·n
+                let mut next_n = Integer::from(&self.q * 3u32) + &self.r;
+                next_n *= self.base;
+                next_n /= &self.t;
+                next_n -= Integer::from(&self.n * self.base);
+
+                self.r -= Integer::from(&self.n * &self.t);
+                self.r *= self.base;
+                self.q *= self.base;
+                self.n = next_n;
+                return Some(digit);
+            }
+
+            // Absorb the next term: x ↦ 2 + k·x / (2k + 1)
+            let mut r = Integer::from(&self.q * 2u32) + &self.r;
+            r *= self.l;
+            let mut n = Integer::from(&self.q * (7 * self.k + 2)) + Integer::from(&self.r * self.l);
+            self.t *= self.l;
+            n /= &self.t;
+
+            self.q *= self.k;
+            self.r = r;
+            self.n = n;
+            self.k += 1;
+            self.l += 2;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::write_stream;
+    use crate::{ComputeOptions, compute_pi_with};
+
+    #[test]
+    fn matches_compute_pi() {
+        for base in [10, 2] {
+            let options = ComputeOptions {
+                base,
+                ..ComputeOptions::default()
+            };
+            let expected = compute_pi_with(500, &options).unwrap().to_digit_string();
+            let mut streamed = Vec::new();
+            write_stream(&mut streamed, Spigot::new(base), base, Some(500)).unwrap();
+            assert_eq!(
+                String::from_utf8(streamed).unwrap(),
+                expected + "\n",
+                "base {}",
+                base
+            );
+        }
+    }
+}
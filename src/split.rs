@@ -0,0 +1,136 @@
+//! Top-level driver for binary splitting over [0, terms).
+//!
+//! The top of the recursion tree is walked here, down to "units" of about
+//! `terms / UNITS` terms which are handed to `split_parallel`. Each
+//! finished unit and each merge above them is a point where a checkpoint
+//! can be saved or loaded and progress can be reported.
+
+use crate::checkpoint::Checkpoint;
+use crate::hypergeometric::{Hypergeometric, merge, merge_parallel, split_parallel};
+use crate::progress::Progress;
+use rug::Integer;
+use std::thread;
+
+/// Target number of units per run.
+const UNITS: u64 = 64;
+
+/// Optional bookkeeping attached to a binary splitting run.
+pub struct Tracking<'a> {
+    /// Series being summed
+    pub series: &'a dyn Hypergeometric,
+    /// Largest range handed to `split_parallel` as one unit
+    pub unit: u64,
+    pub checkpoint: Option<&'a Checkpoint>,
+    pub progress: Option<&'a Progress>,
+}
+
+impl<'a> Tracking<'a> {
+    /// Tracking for a run of `series` over [0, terms).
+    pub fn new(
+        series: &'a dyn Hypergeometric,
+        terms: u64,
+        checkpoint: Option<&'a Checkpoint>,
+        progress: Option<&'a Progress>,
+    ) -> Tracking<'a> {
+        Tracking {
+            series,
+            unit: (terms / UNITS).max(1),
+            checkpoint,
+            progress,
+        }
+    }
+
+    /// Estimated cost of computing [a, b) from scratch, in the same units
+    /// as the values passed to `Progress::series_advance`.
+    pub fn work(&self, a: u64, b: u64) -> f64 {
+        if b - a <= self.unit {
+            // log2(b - a) levels below, each touching about range_bits bits
+            let bits = self.range_bits(a, b);
+            bits * bits.log2() * ((b - a) as f64).log2().max(1.0)
+        } else {
+            let m = (a + b) / 2;
+            self.work(a, m) + self.work(m, b) + self.merge_work(a, b)
+        }
+    }
+
+    fn advance(&self, terms: u64, work: f64) {
+        if let Some(progress) = self.progress {
+            progress.series_advance(terms, work);
+        }
+    }
+
+    /// Approximate size in bits of Q(a, b), from the size of its middle
+    /// term (3 log2(k) + 53.3 bits for Chudnovsky).
+    fn range_bits(&self, a: u64, b: u64) -> f64 {
+        (b - a) as f64 * self.series.term_bits((a + b) / 2)
+    }
+
+    /// Merge cost grows like the operand size times its logarithm.
+    fn merge_work(&self, a: u64, b: u64) -> f64 {
+        let bits = self.range_bits(a, b);
+        bits * bits.log2()
+    }
+}
+
+/// Binary splitting of [a, b) with checkpointing and progress reporting.
+///
+/// The thread budget is split between halves as in `split_parallel`,
+/// and the result is bit-identical to it.
+pub fn binary_split_tracked(
+    a: u64,
+    b: u64,
+    threads: usize,
+    tracking: &Tracking,
+) -> Result<(Integer, Integer, Integer), String> {
+    if let Some(ckpt) = tracking.checkpoint
+        && let Some(pqt) = ckpt.load(a, b)?
+    {
+        tracking.advance(b - a, tracking.work(a, b));
+        return Ok(pqt);
+    }
+
+    let pqt = if b - a <= tracking.unit {
+        let pqt = split_parallel(tracking.series, a, b, threads);
+        tracking.advance(b - a, tracking.work(a, b));
+        pqt
+    } else {
+        let m = (a + b) / 2;
+
+        let (left, right) = if threads > 1 {
+            let left_threads = threads / 2;
+            let right_threads = threads - left_threads;
+            thread::scope(|s| {
+                let left = s.spawn(|| binary_split_tracked(a, m, left_threads, tracking));
+                let right = binary_split_tracked(m, b, right_threads, tracking);
+                (
+                    left.join().expect("binary splitting thread panicked"),
+                    right,
+                )
+            })
+        } else {
+            (
+                binary_split_tracked(a, m, 1, tracking),
+                binary_split_tracked(m, b, 1, tracking),
+            )
+        };
+        let (left, right) = (left?, right?);
+
+        let pqt = if threads > 1 {
+            merge_parallel(left, right, threads)
+        } else {
+            merge(left, right)
+        };
+        tracking.advance(0, tracking.merge_work(a, b));
+        pqt
+    };
+
+    if let Some(ckpt) = tracking.checkpoint {
+        ckpt.save(a, b, &pqt)?;
+        if b - a > tracking.unit {
+            let m = (a + b) / 2;
+            ckpt.discard(a, m);
+            ckpt.discard(m, b);
+        }
+    }
+    Ok(pqt)
+}
@@ -0,0 +1,176 @@
+//! Independent checks of a finished computation.
+//!
+//! `verify_bbp` compares the last hex digits a `PiResult` determines against
+//! the same positions extracted with BBP (`bbp::hex_digits_at`). The two
+//! methods share no code, and an error anywhere in the series, square root
+//! or division shows up in the final digits, so agreement there checks the
+//! whole run.
+//!
+//! `cross_check` recomputes every digit with a second algorithm (the
+//! Gauss–Legendre iteration for a Chudnovsky result, Chudnovsky for any
+//! other) and reports the first one that differs.
+
+use crate::{
+    Algorithm, ComputeOptions, Constant, PiResult, agm, bbp, chudnovsky_pi, decimal_equivalent,
+    expansion_of, truncate_to_digits,
+};
+use rug::integer::IntegerExt64;
+use rug::{Complete, Integer};
+
+/// Hex digits compared by `verify_bbp`.
+pub const WINDOW: usize = 16;
+
+/// Outcome of comparing a window of hex digits.
+#[derive(Debug, Clone)]
+pub struct Verification {
+    /// Fractional hex position of the first digit compared (1-based)
+    pub position: u64,
+    /// Hex digits taken from the result
+    pub computed: String,
+    /// Hex digits from BBP
+    pub expected: String,
+}
+
+impl Verification {
+    pub fn passed(&self) -> bool {
+        self.computed == self.expected
+    }
+
+    /// Fractional hex position of the last digit compared.
+    pub fn last_position(&self) -> u64 {
+        self.position + self.computed.len() as u64 - 1
+    }
+}
+
+/// Compare the last `WINDOW` hex digits that `result` pins down against BBP.
+pub fn verify_bbp(result: &PiResult) -> Result<Verification, String> {
+    if result.constant != Constant::Pi {
+        return Err("BBP verification only applies to π".to_string());
+    }
+    // Hex digits carried by `digits` digits in `base`, less a byte of slack
+    // so the truncation rarely touches the window
+    let bits = result.digits as f64 * f64::from(result.base).log2();
+    let mut last = ((bits - 8.0) / 4.0).floor().max(0.0) as u64;
+    if last == 0 {
+        return Err("Too few digits to verify".to_string());
+    }
+    last = last.min(bbp::MAX_POSITION);
+
+    // base^digits, the denominator of the result; a shift for powers of two
+    let scale = (!result.base.is_power_of_two())
+        .then(|| Integer::u64_pow_u64(u64::from(result.base), result.digits).complete());
+
+    // Every value in the truncation interval must give the same digits;
+    // step back past runs of F/0 that straddle it
+    let computed = loop {
+        let count = (WINDOW as u64).min(last);
+        if let Some(digits) = hex_window(result, scale.as_ref(), last, count) {
+            break digits;
+        }
+        last -= 1;
+        if last == 0 {
+            return Err("Could not isolate hex digits to verify".to_string());
+        }
+    };
+    let position = last + 1 - computed.len() as u64;
+    let expected = bbp::hex_digits_at(position, computed.len())?;
+
+    Ok(Verification {
+        position,
+        computed,
+        expected,
+    })
+}
+
+/// `count` hex digits of the result ending at fractional position `last`,
+/// or None if they are not determined by the truncated result.
+///
+/// π lies in [I, I + 1) / base^digits, so floor(π 16^last) is known when
+/// both ends of that interval give the same value.
+fn hex_window(result: &PiResult, scale: Option<&Integer>, last: u64, count: u64) -> Option<String> {
+    let floor_at = |i: &Integer| -> Integer {
+        match scale {
+            Some(scale) => Integer::from(i << (4 * last) as usize) / scale,
+            None => {
+                let shift = result.digits * u64::from(result.base.trailing_zeros()) - 4 * last;
+                Integer::from(i >> shift as usize)
+            }
+        }
+    };
+
+    let lo = floor_at(&result.integer);
+    let hi = floor_at(&(result.integer.clone() + 1u32));
+    if lo != hi {
+        return None;
+    }
+
+    let digits = lo.keep_bits_64(4 * count).to_string_radix(16);
+    Some(format!(
+        "{:0>width$}",
+        digits.to_uppercase(),
+        width = count as usize
+    ))
+}
+
+/// Outcome of recomputing a result with a second algorithm.
+#[derive(Debug, Clone)]
+pub struct CrossCheck {
+    /// Name of the algorithm the result was checked against
+    pub algorithm: &'static str,
+    /// Fractional digits compared
+    pub digits: u64,
+    /// 1-based fractional position of the first differing digit, 0 if the
+    /// integer parts differ, None if every digit agrees
+    pub first_difference: Option<u64>,
+}
+
+impl CrossCheck {
+    pub fn passed(&self) -> bool {
+        self.first_difference.is_none()
+    }
+}
+
+/// Recompute `result` with a second algorithm at the same precision and
+/// compare the digits. `expansion` is `result.expansion_string()`, which
+/// the caller has already built.
+pub fn cross_check(result: &PiResult, expansion: &str) -> Result<CrossCheck, String> {
+    if result.constant != Constant::Pi {
+        return Err("Cross-checking only applies to π".to_string());
+    }
+    let (algorithm, pi) = match result.algorithm {
+        Algorithm::Chudnovsky => (Algorithm::Agm, agm::pi_agm(result.precision).0),
+        Algorithm::Agm | Algorithm::Machin => {
+            let decimal_digits = decimal_equivalent(result.digits, result.base);
+            let options = ComputeOptions::default();
+            let (pi, _) = chudnovsky_pi(
+                result.digits,
+                decimal_digits,
+                result.precision,
+                &options,
+                &mut |_| {},
+            )?;
+            (Algorithm::Chudnovsky, pi)
+        }
+    };
+    let integer = truncate_to_digits(&pi, result.digits, result.base);
+
+    let first_difference = (integer != result.integer).then(|| {
+        let other = expansion_of(&integer, result.digits, result.base);
+        let int_len = expansion.len() - result.digits as usize;
+        if other.len() != expansion.len() || other[..int_len] != expansion[..int_len] {
+            return 0;
+        }
+        let offset = expansion[int_len..]
+            .bytes()
+            .zip(other[int_len..].bytes())
+            .position(|(a, b)| a != b)
+            .unwrap_or(0);
+        offset as u64 + 1
+    });
+
+    Ok(CrossCheck {
+        algorithm: algorithm.description(),
+        digits: result.digits,
+        first_difference,
+    })
+}